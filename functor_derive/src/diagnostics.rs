@@ -0,0 +1,298 @@
+//! The derive reports fields that contain a mapped parameter in a position that cannot be mapped.
+//! Each example below is rejected with the diagnostic in its heading, and compiles once the field or option that the
+//! diagnostic points at is fixed or removed. Errors of the derive itself have no error code, so only the examples that
+//! the compiler rejects name theirs.
+//!
+//! Cannot map the associated type projection `T::Id`, unless a mapping is provided for it:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! trait Lang {
+//!     type Id;
+//! }
+//!
+//! #[derive(Functor)]
+//! struct Ir<T: Lang> {
+//!     id: T::Id,
+//! }
+//! ```
+//!
+//! The target of a projection must project `B`, the type that the parameter is mapped to:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! trait Lang {
+//!     type Id;
+//! }
+//!
+//! fn map_id<A: Lang, B: Lang>(_id: A::Id) -> B::Id {
+//!     unimplemented!()
+//! }
+//!
+//! #[derive(Functor)]
+//! #[functor(T::Id => Q::Id via map_id)]
+//! struct Ir<T: Lang> {
+//!     id: T::Id,
+//! }
+//! ```
+//!
+//! The target of a projection must be qualified by the same trait as the projection:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! trait Lang {
+//!     type Id;
+//! }
+//!
+//! trait Other {
+//!     type Id;
+//! }
+//!
+//! fn map_id<A: Lang, B: Lang>(_id: A::Id) -> B::Id {
+//!     unimplemented!()
+//! }
+//!
+//! #[derive(Functor)]
+//! #[functor(<T as Lang>::Id => <B as Other>::Id via map_id)]
+//! struct Ir<T: Lang> {
+//!     id: <T as Lang>::Id,
+//! }
+//! ```
+//!
+//! Cannot map a projection qualified by a different trait than the one a mapping is provided for:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! trait Lang {
+//!     type Id;
+//! }
+//!
+//! trait Other {
+//!     type Id;
+//! }
+//!
+//! fn map_id<A: Lang, B: Lang>(_id: A::Id) -> B::Id {
+//!     unimplemented!()
+//! }
+//!
+//! #[derive(Functor)]
+//! #[functor(<T as Lang>::Id => <B as Lang>::Id via map_id)]
+//! struct Ir<T: Lang + Other> {
+//!     id: <T as Other>::Id,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside a boxed iterator without mapping it lazily:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! struct Pending<T> {
+//!     items: Box<dyn Iterator<Item = T>>,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside a boxed closure without mapping it lazily:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! struct Deferred<T> {
+//!     value: Box<dyn FnOnce() -> T>,
+//! }
+//! ```
+//!
+//! Fields of lazily mapped types require `#[functor(lazy)]` on the type that holds them:
+//!
+//! ```compile_fail,E0277
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! #[functor(lazy)]
+//! struct Pending<T> {
+//!     items: Box<dyn Iterator<Item = T>>,
+//! }
+//!
+//! #[derive(Functor)]
+//! struct Batch<T> {
+//!     pending: Pending<T>,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside a boxed `Fn` closure:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! #[functor(lazy)]
+//! struct Deferred<T> {
+//!     value: Box<dyn Fn() -> T>,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside a boxed future:
+//!
+//! ```compile_fail
+//! use core::future::Future;
+//! use core::pin::Pin;
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! #[functor(lazy)]
+//! struct Task<T> {
+//!     value: T,
+//!     next: Pin<Box<dyn Future<Output = T>>>,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside the self type of a qualified path:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! struct Items<T> {
+//!     first: T,
+//!     rest: <Vec<T> as IntoIterator>::IntoIter,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside more than one generic argument of a type that is mapped through the `Functor` trait:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! struct Pair<A, B>(A, B);
+//!
+//! #[derive(Functor)]
+//! #[functor(Pair: Functor)]
+//! struct Both<T> {
+//!     value: T,
+//!     pair: Pair<T, T>,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside the hasher of `HashMap`:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Functor)]
+//! struct Table<T> {
+//!     hasher: T,
+//!     entries: HashMap<u8, u8, T>,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside the associated type binding `Item`:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//! use std::rc::Rc;
+//!
+//! #[derive(Functor)]
+//! struct Items<T> {
+//!     first: T,
+//!     rest: Rc<dyn Iterator<Item = T>>,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside the `Fn`-style arguments of `Fn`:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//! use std::rc::Rc;
+//!
+//! #[derive(Functor)]
+//! struct Callback<T> {
+//!     value: T,
+//!     callback: Rc<dyn Fn(T)>,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside a function pointer:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! struct Callback<T> {
+//!     value: T,
+//!     callback: fn(T),
+//! }
+//! ```
+//!
+//! Cannot map `T` behind a reference:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! struct Borrowed<'a, T> {
+//!     value: T,
+//!     name: &'a str,
+//!     borrowed: &'a T,
+//! }
+//! ```
+//!
+//! Cannot map `T` behind a raw pointer:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! struct Raw<T> {
+//!     value: T,
+//!     raw: *const T,
+//! }
+//! ```
+//!
+//! Cannot map `T` inside a trait object:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! struct Viewed<T> {
+//!     value: T,
+//!     viewed: Box<dyn AsRef<T>>,
+//! }
+//! ```
+//!
+//! Other options report parameters they cannot be generated for. Cannot generate `fmap_all_choices` for `T`, because it
+//! is bounded or mapped lazily:
+//!
+//! ```compile_fail
+//! use functor_derive::Functor;
+//!
+//! #[derive(Functor)]
+//! #[functor(choices)]
+//! struct Sorted<T: Ord> {
+//!     values: Vec<T>,
+//! }
+//! ```
+//!
+//! `from_fn` and `replicate` can only be called if the fields they fill with their default implement `Default`:
+//!
+//! ```compile_fail,E0277
+//! use functor_derive::Functor;
+//!
+//! struct Id(u32);
+//!
+//! #[derive(Functor)]
+//! #[functor(from_fn)]
+//! struct Named<T> {
+//!     value: T,
+//!     id: Id,
+//! }
+//!
+//! let named = Named::<u8>::replicate(1);
+//! ```
@@ -1,5 +1,23 @@
-use crate::{Functor, Functor0, Functor1, FunctorOrdKeys, FunctorValues};
-use std::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use crate::{
+    family_impl, Functor, Functor0, Functor1, FunctorFamily, FunctorOrdEntries, FunctorOrdKeys,
+    FunctorValues, KeyCollision,
+};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::{
+    binary_heap, btree_map, btree_set, linked_list, vec_deque, BTreeMap, BTreeSet, BinaryHeap,
+    LinkedList, VecDeque,
+};
+use alloc::rc::Rc;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+use core::future::{poll_fn, Future};
+use core::pin::Pin;
+use core::task::Poll;
 
 impl<A> Functor<A> for Vec<A> {
     type Target<B> = Vec<B>;
@@ -13,6 +31,8 @@ impl<A> Functor<A> for Vec<A> {
     }
 }
 
+family_impl!([T] Vec<T> => Vec<A>);
+
 impl<A> Functor0<A> for Vec<A> {
     type Target<B> = Vec<B>;
 
@@ -37,6 +57,8 @@ impl<A> Functor<A> for Box<A> {
     }
 }
 
+family_impl!([T] Box<T> => Box<A>);
+
 impl<A> Functor0<A> for Box<A> {
     type Target<B> = Box<B>;
 
@@ -49,6 +71,249 @@ impl<A> Functor0<A> for Box<A> {
     }
 }
 
+/// The elements are mapped through a `Vec`, which lets the standard library reuse the allocation
+/// when `A` and `B` have the same size and alignment.
+impl<A> Functor<A> for Box<[A]> {
+    type Target<B> = Box<[B]>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+family_impl!([T] Box<[T]> => Box<[A]>);
+
+impl<A> Functor0<A> for Box<[A]> {
+    type Target<B> = Box<[B]>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Vec::from(self)
+            .into_iter()
+            .map(f)
+            .collect::<Vec<_>>()
+            .into_boxed_slice()
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        Vec::from(self)
+            .into_iter()
+            .map(f)
+            .collect::<Result<Vec<_>, E>>()
+            .map(Vec::into_boxed_slice)
+    }
+}
+
+impl<A: Unpin> Functor<A> for Pin<Box<A>> {
+    type Target<B> = Pin<Box<B>>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        Box::pin(f(*Pin::into_inner(self)))
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(*Pin::into_inner(self)).map(Box::pin)
+    }
+}
+
+/// Maps the `Box` inside a `Pin`, which is used by derived types since the argument of `Pin` is the
+/// pointer rather than the value. Unpinning is sound because the value is required to be `Unpin`.
+#[doc(hidden)]
+pub trait FunctorPinBox<A: Unpin>: Sized {
+    fn __fmap_0_ref<B>(self, f: &impl Fn(Box<A>) -> Box<B>) -> Pin<Box<B>>;
+
+    fn __try_fmap_0_ref<B, E>(
+        self,
+        f: &impl Fn(Box<A>) -> Result<Box<B>, E>,
+    ) -> Result<Pin<Box<B>>, E>;
+}
+
+impl<A: Unpin> FunctorPinBox<A> for Pin<Box<A>> {
+    fn __fmap_0_ref<B>(self, f: &impl Fn(Box<A>) -> Box<B>) -> Pin<Box<B>> {
+        Box::into_pin(f(Pin::into_inner(self)))
+    }
+
+    fn __try_fmap_0_ref<B, E>(
+        self,
+        f: &impl Fn(Box<A>) -> Result<Box<B>, E>,
+    ) -> Result<Pin<Box<B>>, E> {
+        f(Pin::into_inner(self)).map(Box::into_pin)
+    }
+}
+
+/// Maps the value in place if this is the only `Rc` pointing to it, otherwise the value is cloned
+/// out of the allocation first and the other owners keep the original.
+impl<A: Clone> Functor<A> for Rc<A> {
+    type Target<B> = Rc<B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+impl<A: Clone> Functor0<A> for Rc<A> {
+    type Target<B> = Rc<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Rc::new(f(Rc::unwrap_or_clone(self)))
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(Rc::unwrap_or_clone(self)).map(Rc::new)
+    }
+}
+
+/// Slices cannot be moved out of an `Rc`, so every element is cloned and the mapped elements are
+/// collected into a new allocation, regardless of whether the slice is shared.
+impl<A: Clone> Functor<A> for Rc<[A]> {
+    type Target<B> = Rc<[B]>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+impl<A: Clone> Functor0<A> for Rc<[A]> {
+    type Target<B> = Rc<[B]>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.iter().cloned().map(f).collect()
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.iter().cloned().map(f).collect()
+    }
+}
+
+/// Maps the value in place if this is the only `Arc` pointing to it, otherwise the value is cloned
+/// out of the allocation first and the other owners keep the original.
+#[cfg(target_has_atomic = "ptr")]
+impl<A: Clone> Functor<A> for Arc<A> {
+    type Target<B> = Arc<B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<A: Clone> Functor0<A> for Arc<A> {
+    type Target<B> = Arc<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Arc::new(f(Arc::unwrap_or_clone(self)))
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(Arc::unwrap_or_clone(self)).map(Arc::new)
+    }
+}
+
+/// Slices cannot be moved out of an `Arc`, so every element is cloned and the mapped elements are
+/// collected into a new allocation, regardless of whether the slice is shared.
+#[cfg(target_has_atomic = "ptr")]
+impl<A: Clone> Functor<A> for Arc<[A]> {
+    type Target<B> = Arc<[B]>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<A: Clone> Functor0<A> for Arc<[A]> {
+    type Target<B> = Arc<[B]>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.iter().cloned().map(f).collect()
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.iter().cloned().map(f).collect()
+    }
+}
+
+/// The mapped type is at position 1, after the lifetime of the `Cow`.
+#[doc(hidden)]
+pub trait FunctorCow<'a, A: Clone + 'a>: Sized {
+    type Target<B: Clone + 'a>;
+
+    fn fmap<B: Clone + 'a>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_1_ref(&f)
+    }
+
+    fn try_fmap<B: Clone + 'a, E>(
+        self,
+        f: impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_1_ref(&f)
+    }
+
+    fn __fmap_1_ref<B: Clone + 'a>(self, f: &impl Fn(A) -> B) -> Self::Target<B>;
+
+    fn __try_fmap_1_ref<B: Clone + 'a, E>(
+        self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E>;
+}
+
+/// Owned values are mapped in place, borrowed values are cloned through `ToOwned` first.
+/// The result is always `Cow::Owned`.
+impl<'a, A: Clone> FunctorCow<'a, A> for Cow<'a, A> {
+    type Target<B: Clone + 'a> = Cow<'a, B>;
+
+    fn __fmap_1_ref<B: Clone + 'a>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Cow::Owned(f(self.into_owned()))
+    }
+
+    fn __try_fmap_1_ref<B: Clone + 'a, E>(
+        self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        f(self.into_owned()).map(Cow::Owned)
+    }
+}
+
+/// Owned vectors are mapped without cloning, borrowed slices are cloned through `ToOwned` first.
+/// The result is always `Cow::Owned`.
+impl<'a, A: Clone> FunctorCow<'a, A> for Cow<'a, [A]> {
+    type Target<B: Clone + 'a> = Cow<'a, [B]>;
+
+    fn __fmap_1_ref<B: Clone + 'a>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Cow::Owned(self.into_owned().into_iter().map(f).collect())
+    }
+
+    fn __try_fmap_1_ref<B: Clone + 'a, E>(
+        self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        self.into_owned()
+            .into_iter()
+            .map(f)
+            .collect::<Result<_, E>>()
+            .map(Cow::Owned)
+    }
+}
+
 impl<A> Functor<A> for VecDeque<A> {
     type Target<B> = VecDeque<B>;
 
@@ -61,6 +326,8 @@ impl<A> Functor<A> for VecDeque<A> {
     }
 }
 
+family_impl!([T] VecDeque<T> => VecDeque<A>);
+
 impl<A> Functor0<A> for VecDeque<A> {
     type Target<B> = VecDeque<B>;
 
@@ -85,6 +352,8 @@ impl<A> Functor<A> for LinkedList<A> {
     }
 }
 
+family_impl!([T] LinkedList<T> => LinkedList<A>);
+
 impl<A> Functor0<A> for LinkedList<A> {
     type Target<B> = LinkedList<B>;
 
@@ -110,8 +379,11 @@ impl<K: Ord, A> Functor<A> for BTreeMap<K, A> {
     }
 }
 
+family_impl!([K: Ord, T] BTreeMap<K, T> => BTreeMap<K, A>);
+
 impl<A: Ord, V> FunctorOrdKeys<A> for BTreeMap<A, V> {
     type Target<B: Ord> = BTreeMap<B, V>;
+    type Value = V;
 
     fn __fmap_0_ref<B: Ord>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
         self.into_iter().map(|(k, v)| (f(k), v)).collect()
@@ -125,6 +397,63 @@ impl<A: Ord, V> FunctorOrdKeys<A> for BTreeMap<A, V> {
             .map(|(k, v)| f(k).map(|k| (k, v)))
             .collect()
     }
+
+    fn __try_fmap_keys_merge<B: Ord, E>(
+        self,
+        f: &impl Fn(A) -> B,
+        merge: &impl Fn(B, V, V) -> Result<(B, V), E>,
+    ) -> Result<Self::Target<B>, E> {
+        merge_btree_map(self.into_iter().map(|(k, v)| (f(k), v)), merge)
+    }
+}
+
+impl<K: Ord, V> FunctorOrdEntries<K, V> for BTreeMap<K, V> {
+    type Target<K2: Ord, V2> = BTreeMap<K2, V2>;
+
+    fn fmap_entries<K2: Ord, V2>(self, f: impl Fn(K, V) -> (K2, V2)) -> Self::Target<K2, V2> {
+        self.into_iter().map(|(k, v)| f(k, v)).collect()
+    }
+
+    fn try_fmap_entries<K2: Ord, V2, E>(
+        self,
+        f: impl Fn(K, V) -> Result<(K2, V2), E>,
+    ) -> Result<Self::Target<K2, V2>, E> {
+        self.into_iter().map(|(k, v)| f(k, v)).collect()
+    }
+}
+
+/// Collects the entries into a map, combining the values of equal keys with `merge`.
+fn merge_btree_map<K: Ord, V, E>(
+    entries: impl Iterator<Item = (K, V)>,
+    merge: &impl Fn(K, V, V) -> Result<(K, V), E>,
+) -> Result<BTreeMap<K, V>, E> {
+    let mut map = BTreeMap::new();
+    for (k, v) in entries {
+        match map.entry(k) {
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(v);
+            }
+            btree_map::Entry::Occupied(entry) => {
+                let (k, first) = entry.remove_entry();
+                let (k, v) = merge(k, first, v)?;
+                map.insert(k, v);
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// Collects the elements into a set, failing on the first element that is already present.
+fn strict_btree_set<A: Ord>(
+    elements: impl Iterator<Item = A>,
+) -> Result<BTreeSet<A>, KeyCollision<A>> {
+    let mut set = BTreeSet::new();
+    for element in elements {
+        if let Some(key) = set.replace(element) {
+            return Err(KeyCollision { key });
+        }
+    }
+    Ok(set)
 }
 
 impl<K: Ord, A> FunctorValues<A> for BTreeMap<K, A> {
@@ -166,6 +495,13 @@ impl<A: Ord> FunctorOrd<A> for BTreeSet<A> {
     ) -> Result<Self::Target<B>, E> {
         self.into_iter().map(f).collect()
     }
+
+    fn try_fmap_strict<B: Ord>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>> {
+        strict_btree_set(self.into_iter().map(f))
+    }
 }
 
 #[doc(hidden)]
@@ -180,6 +516,12 @@ pub trait FunctorOrd<A: Ord>: Sized {
         self.__try_fmap_0_ref(&f)
     }
 
+    /// Fails with the colliding element when two elements are mapped to the same element.
+    fn try_fmap_strict<B: Ord>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>>;
+
     fn __fmap_0_ref<B: Ord>(self, f: &impl Fn(A) -> B) -> Self::Target<B>;
 
     fn __try_fmap_0_ref<B: Ord, E>(
@@ -201,4 +543,423 @@ impl<A: Ord> FunctorOrd<A> for BinaryHeap<A> {
     ) -> Result<Self::Target<B>, E> {
         self.into_iter().map(f).collect()
     }
+
+    /// Heaps keep duplicate elements, so this never fails.
+    fn try_fmap_strict<B: Ord>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>> {
+        Ok(self.__fmap_0_ref(&f))
+    }
+}
+
+// Iterators keep their type, so the remaining items are mapped and collected like the collection they iterate over.
+impl<A> Functor<A> for vec::IntoIter<A> {
+    type Target<B> = vec::IntoIter<B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+family_impl!([T] vec::IntoIter<T> => vec::IntoIter<A>);
+
+impl<A> Functor0<A> for vec::IntoIter<A> {
+    type Target<B> = vec::IntoIter<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(f).collect::<Vec<_>>().into_iter()
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.map(f)
+            .collect::<Result<Vec<_>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+}
+
+impl<A> Functor<A> for vec_deque::IntoIter<A> {
+    type Target<B> = vec_deque::IntoIter<B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+family_impl!([T] vec_deque::IntoIter<T> => vec_deque::IntoIter<A>);
+
+impl<A> Functor0<A> for vec_deque::IntoIter<A> {
+    type Target<B> = vec_deque::IntoIter<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(f).collect::<VecDeque<_>>().into_iter()
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.map(f)
+            .collect::<Result<VecDeque<_>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+}
+
+impl<A> Functor<A> for linked_list::IntoIter<A> {
+    type Target<B> = linked_list::IntoIter<B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+family_impl!([T] linked_list::IntoIter<T> => linked_list::IntoIter<A>);
+
+impl<A> Functor0<A> for linked_list::IntoIter<A> {
+    type Target<B> = linked_list::IntoIter<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(f).collect::<LinkedList<_>>().into_iter()
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.map(f)
+            .collect::<Result<LinkedList<_>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+}
+
+impl<K: Ord, A> Functor<A> for btree_map::IntoIter<K, A> {
+    type Target<B> = btree_map::IntoIter<K, B>;
+
+    /// By default BTreeMap iterators map their Value generic.
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_1_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_1_ref(&f)
+    }
+}
+
+family_impl!([K: Ord, T] btree_map::IntoIter<K, T> => btree_map::IntoIter<K, A>);
+
+impl<A: Ord, V> FunctorOrdKeys<A> for btree_map::IntoIter<A, V> {
+    type Target<B: Ord> = btree_map::IntoIter<B, V>;
+    type Value = V;
+
+    fn __fmap_0_ref<B: Ord>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(|(k, v)| (f(k), v))
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+    }
+
+    fn __try_fmap_0_ref<B: Ord, E>(
+        self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        self.map(|(k, v)| f(k).map(|k| (k, v)))
+            .collect::<Result<BTreeMap<_, _>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+
+    fn __try_fmap_keys_merge<B: Ord, E>(
+        self,
+        f: &impl Fn(A) -> B,
+        merge: &impl Fn(B, V, V) -> Result<(B, V), E>,
+    ) -> Result<Self::Target<B>, E> {
+        merge_btree_map(self.map(|(k, v)| (f(k), v)), merge).map(IntoIterator::into_iter)
+    }
+}
+
+impl<K: Ord, A> FunctorValues<A> for btree_map::IntoIter<K, A> {
+    type Target<B> = btree_map::IntoIter<K, B>;
+
+    fn fmap_values<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_1_ref(&f)
+    }
+
+    fn try_fmap_values<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_1_ref(&f)
+    }
+}
+
+impl<K: Ord, A> Functor1<A> for btree_map::IntoIter<K, A> {
+    type Target<B> = btree_map::IntoIter<K, B>;
+
+    fn __fmap_1_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(|(k, v)| (k, f(v)))
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+    }
+
+    fn __try_fmap_1_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.map(|(k, v)| f(v).map(|v| (k, v)))
+            .collect::<Result<BTreeMap<_, _>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+}
+
+impl<A: Ord> FunctorOrd<A> for btree_set::IntoIter<A> {
+    type Target<B: Ord> = btree_set::IntoIter<B>;
+
+    fn __fmap_0_ref<B: Ord>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(f).collect::<BTreeSet<_>>().into_iter()
+    }
+
+    fn __try_fmap_0_ref<B: Ord, E>(
+        self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        self.map(f)
+            .collect::<Result<BTreeSet<_>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+
+    fn try_fmap_strict<B: Ord>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>> {
+        strict_btree_set(self.map(f)).map(IntoIterator::into_iter)
+    }
+}
+
+impl<A: Ord> FunctorOrd<A> for binary_heap::IntoIter<A> {
+    type Target<B: Ord> = binary_heap::IntoIter<B>;
+
+    fn __fmap_0_ref<B: Ord>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(f).collect::<BinaryHeap<_>>().into_iter()
+    }
+
+    fn __try_fmap_0_ref<B: Ord, E>(
+        self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        self.map(f)
+            .collect::<Result<BinaryHeap<_>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+
+    /// Heaps keep duplicate elements, so this never fails.
+    fn try_fmap_strict<B: Ord>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>> {
+        Ok(self.__fmap_0_ref(&f))
+    }
+}
+
+/// Awaits a structure of futures, like `Vec<F>` or a derived type, into the same structure of their outputs.
+/// The futures run concurrently and the structure is rebuilt once all of them have finished.
+pub trait FunctorJoin<A: Future>: FunctorFamily<Member<A> = Self> {
+    fn join_all(self) -> impl Future<Output = Self::Member<A::Output>>;
+}
+
+impl<A: Future, X: FunctorFamily<Member<A> = X>> FunctorJoin<A> for X {
+    async fn join_all(self) -> X::Member<A::Output> {
+        // Move the futures out of the structure, leaving their indices in their place.
+        let pending = RefCell::new(Vec::new());
+        let indices = X::fmap_member(self, |future: A| {
+            let mut pending = pending.borrow_mut();
+            pending.push((Box::pin(future), None));
+            pending.len() - 1
+        });
+
+        let mut pending = pending.into_inner();
+        poll_fn(|cx| {
+            let mut is_done = true;
+            for (future, output) in &mut pending {
+                if output.is_none() {
+                    match future.as_mut().poll(cx) {
+                        Poll::Ready(value) => *output = Some(value),
+                        Poll::Pending => is_done = false,
+                    }
+                }
+            }
+            if is_done {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        let outputs = RefCell::new(pending);
+        X::fmap_member(indices, |index: usize| {
+            outputs.borrow_mut()[index]
+                .1
+                .take()
+                .expect("every index refers to a finished future")
+        })
+    }
+}
+
+/// Maps every value to each of its alternatives, giving the structures for every combination of choices.
+/// This traverses the structure in the list applicative, like `Vec<A>` into `Vec<Vec<B>>` with each element chosen.
+/// It is available for all functor families whose structure can be cloned, such as `Vec`, `Option`, arrays and derived
+/// types that also derive `Clone`. Derived types that are no functor family, such as those with lifetime parameters,
+/// generate the same methods with the `choices` option.
+pub trait FunctorChoices<A>: FunctorFamily<Member<A> = Self> + Sized {
+    /// Returns the Cartesian product of the alternatives, where later values vary fastest.
+    fn fmap_all_choices<B: Clone>(self, f: impl Fn(A) -> Vec<B>) -> Vec<Self::Member<B>>
+    where
+        Self::Member<usize>: Clone,
+    {
+        self.fmap_all_choices_iter(f).collect()
+    }
+
+    /// Returns the Cartesian product of the alternatives lazily, so that it is never materialised.
+    /// The alternatives of each value are still computed up front.
+    fn fmap_all_choices_iter<B: Clone>(
+        self,
+        f: impl Fn(A) -> Vec<B>,
+    ) -> impl Iterator<Item = Self::Member<B>>
+    where
+        Self::Member<usize>: Clone,
+    {
+        __all_choices(
+            |index| Self::fmap_member(self, index),
+            f,
+            |shape, pick| Self::fmap_member(shape, pick),
+        )
+    }
+}
+
+impl<A, X: FunctorFamily<Member<A> = X>> FunctorChoices<A> for X {}
+
+#[doc(hidden)]
+pub use alloc::vec::Vec as __Vec;
+
+/// Iterates over the combinations of choices of a structure, which `index` maps to its shape by replacing every value
+/// with the index of its alternatives. The shape is rebuilt for each combination by `rebuild`.
+#[doc(hidden)]
+pub fn __all_choices<A, S: Clone, B: Clone, T>(
+    index: impl FnOnce(&dyn Fn(A) -> usize) -> S,
+    f: impl Fn(A) -> Vec<B>,
+    rebuild: impl Fn(S, &dyn Fn(usize) -> B) -> T,
+) -> impl Iterator<Item = T> {
+    let choices = RefCell::new(Vec::new());
+    let shape = index(&|value: A| {
+        let mut choices = choices.borrow_mut();
+        choices.push(f(value));
+        choices.len() - 1
+    });
+    let choices = choices.into_inner();
+
+    let picks = choices
+        .iter()
+        .all(|alternatives| !alternatives.is_empty())
+        .then(|| vec![0; choices.len()]);
+
+    AllChoices {
+        shape,
+        choices,
+        picks,
+        rebuild,
+    }
+}
+
+/// Iterates over the combinations of choices, counting `picks` up like an odometer.
+struct AllChoices<S, B, R> {
+    shape: S,
+    choices: Vec<Vec<B>>,
+    picks: Option<Vec<usize>>,
+    rebuild: R,
+}
+
+impl<S: Clone, B: Clone, T, R: Fn(S, &dyn Fn(usize) -> B) -> T> Iterator for AllChoices<S, B, R> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let picks = self.picks.as_mut()?;
+        let item = (self.rebuild)(self.shape.clone(), &|index: usize| {
+            self.choices[index][picks[index]].clone()
+        });
+
+        // Advance to the next combination, which is done once every position has wrapped around.
+        let mut is_done = true;
+        for (pick, alternatives) in picks.iter_mut().zip(&self.choices).rev() {
+            *pick += 1;
+            if *pick < alternatives.len() {
+                is_done = false;
+                break;
+            }
+            *pick = 0;
+        }
+        if is_done {
+            self.picks = None;
+        }
+
+        Some(item)
+    }
+}
+
+/// Maps a recursive structure with `#[functor(stack_safe)]` without recursing on the call stack.
+/// Each node is unfolded into a layer whose children are replaced by their indices, after which the layers are folded
+/// back up from the last index.
+#[doc(hidden)]
+pub fn __map_stack_safe<N, L, O>(
+    root: N,
+    unfold: impl Fn(N, &dyn Fn(N) -> usize) -> L,
+    fold: impl Fn(L, &dyn Fn(usize) -> O) -> O,
+) -> O {
+    match __try_map_stack_safe(
+        root,
+        |node, push| Ok::<_, Infallible>(unfold(node, push)),
+        fold,
+    ) {
+        Ok(output) => output,
+        Err(never) => match never {},
+    }
+}
+
+#[doc(hidden)]
+pub fn __try_map_stack_safe<N, L, O, E>(
+    root: N,
+    unfold: impl Fn(N, &dyn Fn(N) -> usize) -> Result<L, E>,
+    fold: impl Fn(L, &dyn Fn(usize) -> O) -> O,
+) -> Result<O, E> {
+    // Nodes are unfolded depth-first, so values are mapped in the same order as when recursing, except that the values
+    // of a node come before those of its children.
+    let mut pending = vec![(0, root)];
+    let count = Cell::new(1);
+    let mut layers = Vec::new();
+    while let Some((index, node)) = pending.pop() {
+        let children = RefCell::new(Vec::new());
+        let layer = unfold(node, &|child| {
+            let index = count.replace(count.get() + 1);
+            children.borrow_mut().push((index, child));
+            index
+        })?;
+        if layers.len() <= index {
+            layers.resize_with(index + 1, || None);
+        }
+        layers[index] = Some(layer);
+        pending.extend(children.into_inner().into_iter().rev());
+    }
+
+    // Children have larger indices than their parent, so folding from the back finds them folded already.
+    let folded = RefCell::new(layers.iter().map(|_| None).collect::<Vec<_>>());
+    for (index, layer) in layers.into_iter().enumerate().rev() {
+        let layer = layer.expect("every index refers to an unfolded node");
+        let output = fold(layer, &|child| {
+            folded.borrow_mut()[child]
+                .take()
+                .expect("every child is folded before its parent")
+        });
+        folded.borrow_mut()[index] = Some(output);
+    }
+    Ok(folded
+        .into_inner()
+        .swap_remove(0)
+        .expect("the root is folded last"))
 }
@@ -1,11 +1,15 @@
 use crate::*;
-use core::cell::{Cell, RefCell, UnsafeCell};
+use core::cell::{Cell, OnceCell, RefCell, UnsafeCell};
+use core::cmp::Reverse;
 use core::marker::PhantomData;
-use core::mem::MaybeUninit;
-use core::ops::ControlFlow;
-use core::{mem, ptr};
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::num::{Saturating, Wrapping};
+use core::ops::{Bound, ControlFlow, Range, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
+use core::task::Poll;
+use core::{mem, option, ptr, result};
 
 functor_impl!(Option);
+family_impl!([T] Option<T> => Option<A>);
 
 impl<A> Functor0<A> for Option<A> {
     type Target<B> = Option<B>;
@@ -32,6 +36,8 @@ impl<A, E> Functor<A> for Result<A, E> {
     }
 }
 
+family_impl!([T, F] Result<T, F> => Result<A, F>);
+
 impl<A, E> Functor0<A> for Result<A, E> {
     type Target<B> = Result<B, E>;
 
@@ -71,6 +77,7 @@ impl<O, A> Functor1<A> for Result<O, A> {
 }
 
 functor_impl!(PhantomData);
+family_impl!([T] PhantomData<T> => PhantomData<A>);
 
 impl<A> Functor0<A> for PhantomData<A> {
     type Target<B> = PhantomData<B>;
@@ -96,6 +103,8 @@ impl<const N: usize, A> Functor<A> for [A; N] {
     }
 }
 
+family_impl!([const N: usize, T] [T; N] => [A; N]);
+
 impl<const N: usize, A> Functor0<A> for [A; N] {
     type Target<B> = [B; N];
 
@@ -176,6 +185,7 @@ impl<const N: usize, A> Functor0<A> for [A; N] {
 }
 
 functor_impl!(Cell);
+family_impl!([T] Cell<T> => Cell<A>);
 
 impl<A> Functor0<A> for Cell<A> {
     type Target<B> = Cell<B>;
@@ -190,6 +200,7 @@ impl<A> Functor0<A> for Cell<A> {
 }
 
 functor_impl!(RefCell);
+family_impl!([T] RefCell<T> => RefCell<A>);
 
 impl<A> Functor0<A> for RefCell<A> {
     type Target<B> = RefCell<B>;
@@ -204,6 +215,7 @@ impl<A> Functor0<A> for RefCell<A> {
 }
 
 functor_impl!(UnsafeCell);
+family_impl!([T] UnsafeCell<T> => UnsafeCell<A>);
 
 impl<A> Functor0<A> for UnsafeCell<A> {
     type Target<B> = UnsafeCell<B>;
@@ -217,6 +229,88 @@ impl<A> Functor0<A> for UnsafeCell<A> {
     }
 }
 
+functor_impl!(OnceCell);
+family_impl!([T] OnceCell<T> => OnceCell<A>);
+
+/// An uninitialized cell stays uninitialized, `f` is not called.
+impl<A> Functor0<A> for OnceCell<A> {
+    type Target<B> = OnceCell<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        match self.into_inner() {
+            Some(v) => OnceCell::from(f(v)),
+            None => OnceCell::new(),
+        }
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        match self.into_inner() {
+            Some(v) => f(v).map(OnceCell::from),
+            None => Ok(OnceCell::new()),
+        }
+    }
+}
+
+functor_impl!(ManuallyDrop);
+family_impl!([T] ManuallyDrop<T> => ManuallyDrop<A>);
+
+impl<A> Functor0<A> for ManuallyDrop<A> {
+    type Target<B> = ManuallyDrop<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        ManuallyDrop::new(f(ManuallyDrop::into_inner(self)))
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(ManuallyDrop::into_inner(self)).map(ManuallyDrop::new)
+    }
+}
+
+functor_impl!(Reverse);
+family_impl!([T] Reverse<T> => Reverse<A>);
+
+impl<A> Functor0<A> for Reverse<A> {
+    type Target<B> = Reverse<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Reverse(f(self.0))
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(self.0).map(Reverse)
+    }
+}
+
+functor_impl!(Wrapping);
+family_impl!([T] Wrapping<T> => Wrapping<A>);
+
+impl<A> Functor0<A> for Wrapping<A> {
+    type Target<B> = Wrapping<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Wrapping(f(self.0))
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(self.0).map(Wrapping)
+    }
+}
+
+functor_impl!(Saturating);
+family_impl!([T] Saturating<T> => Saturating<A>);
+
+impl<A> Functor0<A> for Saturating<A> {
+    type Target<B> = Saturating<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Saturating(f(self.0))
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(self.0).map(Saturating)
+    }
+}
+
 impl<A, C> Functor<A> for ControlFlow<A, C> {
     type Target<B> = ControlFlow<B, C>;
 
@@ -229,6 +323,8 @@ impl<A, C> Functor<A> for ControlFlow<A, C> {
     }
 }
 
+family_impl!([T, C] ControlFlow<T, C> => ControlFlow<A, C>);
+
 impl<A, C> Functor0<A> for ControlFlow<A, C> {
     type Target<B> = ControlFlow<B, C>;
 
@@ -264,3 +360,245 @@ impl<C, A> Functor1<A> for ControlFlow<C, A> {
         })
     }
 }
+
+functor_impl!(Bound);
+family_impl!([T] Bound<T> => Bound<A>);
+
+impl<A> Functor0<A> for Bound<A> {
+    type Target<B> = Bound<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        match self {
+            Bound::Included(v) => Bound::Included(f(v)),
+            Bound::Excluded(v) => Bound::Excluded(f(v)),
+            Bound::Unbounded => Bound::Unbounded,
+        }
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        Ok(match self {
+            Bound::Included(v) => Bound::Included(f(v)?),
+            Bound::Excluded(v) => Bound::Excluded(f(v)?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    }
+}
+
+functor_impl!(Poll);
+family_impl!([T] Poll<T> => Poll<A>);
+
+impl<A> Functor0<A> for Poll<A> {
+    type Target<B> = Poll<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(f)
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        Ok(match self {
+            Poll::Ready(v) => Poll::Ready(f(v)?),
+            Poll::Pending => Poll::Pending,
+        })
+    }
+}
+
+functor_impl!(Range);
+family_impl!([T] Range<T> => Range<A>);
+
+impl<A> Functor0<A> for Range<A> {
+    type Target<B> = Range<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        f(self.start)..f(self.end)
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        Ok(f(self.start)?..f(self.end)?)
+    }
+}
+
+functor_impl!(RangeFrom);
+family_impl!([T] RangeFrom<T> => RangeFrom<A>);
+
+impl<A> Functor0<A> for RangeFrom<A> {
+    type Target<B> = RangeFrom<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        f(self.start)..
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        Ok(f(self.start)?..)
+    }
+}
+
+functor_impl!(RangeInclusive);
+family_impl!([T] RangeInclusive<T> => RangeInclusive<A>);
+
+// Note that an exhausted `RangeInclusive` is not exhausted anymore after mapping.
+impl<A> Functor0<A> for RangeInclusive<A> {
+    type Target<B> = RangeInclusive<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        let (start, end) = self.into_inner();
+        f(start)..=f(end)
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        let (start, end) = self.into_inner();
+        Ok(f(start)?..=f(end)?)
+    }
+}
+
+functor_impl!(RangeTo);
+family_impl!([T] RangeTo<T> => RangeTo<A>);
+
+impl<A> Functor0<A> for RangeTo<A> {
+    type Target<B> = RangeTo<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        ..f(self.end)
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        Ok(..f(self.end)?)
+    }
+}
+
+functor_impl!(RangeToInclusive);
+family_impl!([T] RangeToInclusive<T> => RangeToInclusive<A>);
+
+impl<A> Functor0<A> for RangeToInclusive<A> {
+    type Target<B> = RangeToInclusive<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        ..=f(self.end)
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        Ok(..=f(self.end)?)
+    }
+}
+
+// Iterators cannot be mapped lazily while keeping their type, so the remaining items are mapped eagerly.
+impl<A> Functor<A> for option::IntoIter<A> {
+    type Target<B> = option::IntoIter<B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+family_impl!([T] option::IntoIter<T> => option::IntoIter<A>);
+
+impl<A> Functor0<A> for option::IntoIter<A> {
+    type Target<B> = option::IntoIter<B>;
+
+    fn __fmap_0_ref<B>(mut self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.next().map(f).into_iter()
+    }
+
+    fn __try_fmap_0_ref<B, E>(
+        mut self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        Ok(self.next().map(f).transpose()?.into_iter())
+    }
+}
+
+impl<A> Functor<A> for result::IntoIter<A> {
+    type Target<B> = result::IntoIter<B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_0_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_0_ref(&f)
+    }
+}
+
+family_impl!([T] result::IntoIter<T> => result::IntoIter<A>);
+
+impl<A> Functor0<A> for result::IntoIter<A> {
+    type Target<B> = result::IntoIter<B>;
+
+    fn __fmap_0_ref<B>(mut self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.next().map(f).ok_or(()).into_iter()
+    }
+
+    fn __try_fmap_0_ref<B, E>(
+        mut self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        Ok(self.next().map(f).transpose()?.ok_or(()).into_iter())
+    }
+}
+
+/// Implements the positional trait for the element at `$idx`, the elements around it are kept.
+macro_rules! tuple_impl {
+    ([$($before:ident)*] $idx:tt [$($after:ident)*]) => {
+        paste::paste! {
+            impl<$($before,)* A, $($after,)*> [<Functor $idx>]<A> for ($($before,)* A, $($after,)*) {
+                type Target<B> = ($($before,)* B, $($after,)*);
+
+                #[allow(non_snake_case)]
+                fn [<__fmap_ $idx _ref>]<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+                    let ($($before,)* a, $($after,)*) = self;
+                    ($($before,)* f(a), $($after,)*)
+                }
+
+                #[allow(non_snake_case)]
+                fn [<__try_fmap_ $idx _ref>]<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+                    let ($($before,)* a, $($after,)*) = self;
+                    Ok(($($before,)* f(a)?, $($after,)*))
+                }
+            }
+        }
+    };
+}
+
+/// Implements all positional traits for a tuple, and `Functor` for its last element.
+macro_rules! tuple_impls {
+    ($($typ:ident $idx:tt),+) => {
+        tuple_impls!(@step [] [$($typ $idx)+]);
+    };
+    (@step [$($before:ident)*] [$typ:ident $idx:tt]) => {
+        tuple_impl!([$($before)*] $idx []);
+
+        impl<$($before,)* A> Functor<A> for ($($before,)* A,) {
+            type Target<B> = ($($before,)* B,);
+
+            fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+                paste::paste!(self.[<__fmap_ $idx _ref>](&f))
+            }
+
+            fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+                paste::paste!(self.[<__try_fmap_ $idx _ref>](&f))
+            }
+        }
+
+        family_impl!([$($before,)* T] ($($before,)* T,) => ($($before,)* A,));
+    };
+    (@step [$($before:ident)*] [$typ:ident $idx:tt $($after:ident $after_idx:tt)+]) => {
+        tuple_impl!([$($before)*] $idx [$($after)+]);
+        tuple_impls!(@step [$($before)* $typ] [$($after $after_idx)+]);
+    };
+}
+
+tuple_impls!(T0 0);
+tuple_impls!(T0 0, T1 1);
+tuple_impls!(T0 0, T1 1, T2 2);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3, T4 4);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10);
+tuple_impls!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11);
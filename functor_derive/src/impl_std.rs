@@ -1,11 +1,16 @@
-use crate::{Functor, Functor1, FunctorValues};
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use crate::{family_impl, functor_impl, Functor, Functor0, Functor1, FunctorValues, KeyCollision};
+use std::collections::hash_map::RandomState;
+use std::collections::{hash_map, hash_set, HashMap, HashSet};
+use std::convert::Infallible;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Mutex, OnceLock, PoisonError, RwLock};
 
 #[doc(hidden)]
 pub trait FunctorHashKeys<A: Hash + Eq>: Sized {
     type Target<B: Hash + Eq>;
+    type Value;
 
+    /// When two keys are mapped to the same key, the value that comes last is kept.
     fn fmap_keys<B: Hash + Eq>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
         self.__fmap_0_ref(&f)
     }
@@ -17,12 +22,52 @@ pub trait FunctorHashKeys<A: Hash + Eq>: Sized {
         self.__try_fmap_0_ref(&f)
     }
 
+    /// When two keys are mapped to the same key, their values are combined with `merge`,
+    /// which is given the value that comes first as its first argument.
+    fn fmap_keys_with<B: Hash + Eq>(
+        self,
+        f: impl Fn(A) -> B,
+        merge: impl Fn(Self::Value, Self::Value) -> Self::Value,
+    ) -> Self::Target<B> {
+        match self.__try_fmap_keys_merge(&f, &|k, v1, v2| Ok::<_, Infallible>((k, merge(v1, v2)))) {
+            Ok(target) => target,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Fails with the colliding key when two keys are mapped to the same key.
+    fn try_fmap_keys_strict<B: Hash + Eq>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>> {
+        self.__try_fmap_keys_merge(&f, &|key, _, _| Err(KeyCollision { key }))
+    }
+
     fn __fmap_0_ref<B: Hash + Eq>(self, f: &impl Fn(A) -> B) -> Self::Target<B>;
 
     fn __try_fmap_0_ref<B: Hash + Eq, E>(
         self,
         f: &impl Fn(A) -> Result<B, E>,
     ) -> Result<Self::Target<B>, E>;
+
+    fn __try_fmap_keys_merge<B: Hash + Eq, E>(
+        self,
+        f: &impl Fn(A) -> B,
+        merge: &impl Fn(B, Self::Value, Self::Value) -> Result<(B, Self::Value), E>,
+    ) -> Result<Self::Target<B>, E>;
+}
+
+#[doc(hidden)]
+pub trait FunctorHashEntries<K: Hash + Eq, V>: Sized {
+    type Target<K2: Hash + Eq, V2>;
+
+    /// When two entries are mapped to the same key, the value that comes last is kept.
+    fn fmap_entries<K2: Hash + Eq, V2>(self, f: impl Fn(K, V) -> (K2, V2)) -> Self::Target<K2, V2>;
+
+    fn try_fmap_entries<K2: Hash + Eq, V2, E>(
+        self,
+        f: impl Fn(K, V) -> Result<(K2, V2), E>,
+    ) -> Result<Self::Target<K2, V2>, E>;
 }
 
 #[doc(hidden)]
@@ -40,6 +85,12 @@ pub trait FunctorHashSet<A: Hash + Eq>: Sized {
         self.__try_fmap_0_ref(&f)
     }
 
+    /// Fails with the colliding element when two elements are mapped to the same element.
+    fn try_fmap_strict<B: Hash + Eq>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>>;
+
     fn __fmap_0_ref<B: Hash + Eq>(self, f: &impl Fn(A) -> B) -> Self::Target<B>;
 
     fn __try_fmap_0_ref<B: Hash + Eq, E>(
@@ -48,54 +99,144 @@ pub trait FunctorHashSet<A: Hash + Eq>: Sized {
     ) -> Result<Self::Target<B>, E>;
 }
 
-impl<A: Eq + Hash> FunctorHashSet<A> for HashSet<A> {
-    type Target<B: Hash + Eq> = HashSet<B>;
+impl<A: Eq + Hash, S: BuildHasher + Clone> FunctorHashSet<A> for HashSet<A, S> {
+    type Target<B: Hash + Eq> = HashSet<B, S>;
 
     fn __fmap_0_ref<B: Hash + Eq>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
-        self.into_iter().map(f).collect()
+        let mut set = HashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        set.extend(self.into_iter().map(f));
+        set
     }
 
     fn __try_fmap_0_ref<B: Hash + Eq, E>(
         self,
         f: &impl Fn(A) -> Result<B, E>,
     ) -> Result<Self::Target<B>, E> {
-        self.into_iter().map(f).collect()
+        let mut set = HashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        for v in self {
+            set.insert(f(v)?);
+        }
+        Ok(set)
+    }
+
+    fn try_fmap_strict<B: Hash + Eq>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>> {
+        let hasher = self.hasher().clone();
+        strict_hash_set(self.into_iter().map(f), hasher)
     }
 }
 
-impl<A: Eq + Hash, V> FunctorHashKeys<A> for HashMap<A, V> {
-    type Target<B: Hash + Eq> = HashMap<B, V>;
+impl<A: Eq + Hash, V, S: BuildHasher + Clone> FunctorHashKeys<A> for HashMap<A, V, S> {
+    type Target<B: Hash + Eq> = HashMap<B, V, S>;
+    type Value = V;
 
     fn __fmap_0_ref<B: Hash + Eq>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
-        self.into_iter().map(|(k, v)| (f(k), v)).collect()
+        let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        map.extend(self.into_iter().map(|(k, v)| (f(k), v)));
+        map
     }
 
     fn __try_fmap_0_ref<B: Hash + Eq, E>(
         self,
         f: &impl Fn(A) -> Result<B, E>,
     ) -> Result<Self::Target<B>, E> {
-        self.into_iter()
-            .map(|(k, v)| f(k).map(|k| (k, v)))
-            .collect()
+        let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        for (k, v) in self {
+            map.insert(f(k)?, v);
+        }
+        Ok(map)
+    }
+
+    fn __try_fmap_keys_merge<B: Hash + Eq, E>(
+        self,
+        f: &impl Fn(A) -> B,
+        merge: &impl Fn(B, V, V) -> Result<(B, V), E>,
+    ) -> Result<Self::Target<B>, E> {
+        let hasher = self.hasher().clone();
+        merge_hash_map(self.into_iter().map(|(k, v)| (f(k), v)), hasher, merge)
     }
 }
 
-impl<K: Eq + Hash, A> Functor1<A> for HashMap<K, A> {
-    type Target<B> = HashMap<K, B>;
+impl<K: Eq + Hash, V, S: BuildHasher + Clone> FunctorHashEntries<K, V> for HashMap<K, V, S> {
+    type Target<K2: Hash + Eq, V2> = HashMap<K2, V2, S>;
+
+    fn fmap_entries<K2: Hash + Eq, V2>(self, f: impl Fn(K, V) -> (K2, V2)) -> Self::Target<K2, V2> {
+        let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        map.extend(self.into_iter().map(|(k, v)| f(k, v)));
+        map
+    }
+
+    fn try_fmap_entries<K2: Hash + Eq, V2, E>(
+        self,
+        f: impl Fn(K, V) -> Result<(K2, V2), E>,
+    ) -> Result<Self::Target<K2, V2>, E> {
+        let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        for (k, v) in self {
+            let (k, v) = f(k, v)?;
+            map.insert(k, v);
+        }
+        Ok(map)
+    }
+}
+
+/// Collects the entries into a map, combining the values of equal keys with `merge`.
+fn merge_hash_map<K: Hash + Eq, V, S: BuildHasher, E>(
+    entries: impl Iterator<Item = (K, V)>,
+    hasher: S,
+    merge: &impl Fn(K, V, V) -> Result<(K, V), E>,
+) -> Result<HashMap<K, V, S>, E> {
+    let mut map = HashMap::with_hasher(hasher);
+    for (k, v) in entries {
+        match map.entry(k) {
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(v);
+            }
+            hash_map::Entry::Occupied(entry) => {
+                let (k, first) = entry.remove_entry();
+                let (k, v) = merge(k, first, v)?;
+                map.insert(k, v);
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// Collects the elements into a set, failing on the first element that is already present.
+fn strict_hash_set<A: Hash + Eq, S: BuildHasher>(
+    elements: impl Iterator<Item = A>,
+    hasher: S,
+) -> Result<HashSet<A, S>, KeyCollision<A>> {
+    let mut set = HashSet::with_hasher(hasher);
+    for element in elements {
+        if let Some(key) = set.replace(element) {
+            return Err(KeyCollision { key });
+        }
+    }
+    Ok(set)
+}
+
+impl<K: Eq + Hash, A, S: BuildHasher + Clone> Functor1<A> for HashMap<K, A, S> {
+    type Target<B> = HashMap<K, B, S>;
 
     fn __fmap_1_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
-        self.into_iter().map(|(k, v)| (k, f(v))).collect()
+        let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        map.extend(self.into_iter().map(|(k, v)| (k, f(v))));
+        map
     }
 
     fn __try_fmap_1_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
-        self.into_iter()
-            .map(|(k, v)| f(v).map(|v| (k, v)))
-            .collect()
+        let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        for (k, v) in self {
+            map.insert(k, f(v)?);
+        }
+        Ok(map)
     }
 }
 
-impl<K: Eq + Hash, A> Functor<A> for HashMap<K, A> {
-    type Target<B> = HashMap<K, B>;
+impl<K: Eq + Hash, A, S: BuildHasher + Clone> Functor<A> for HashMap<K, A, S> {
+    type Target<B> = HashMap<K, B, S>;
 
     /// By default HashMaps map their Value generic.
     fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
@@ -107,8 +248,108 @@ impl<K: Eq + Hash, A> Functor<A> for HashMap<K, A> {
     }
 }
 
-impl<K: Eq + Hash, A> FunctorValues<A> for HashMap<K, A> {
-    type Target<B> = HashMap<K, B>;
+family_impl!([K: Eq + Hash, T, S: BuildHasher + Clone] HashMap<K, T, S> => HashMap<K, A, S>);
+
+impl<K: Eq + Hash, A, S: BuildHasher + Clone> FunctorValues<A> for HashMap<K, A, S> {
+    type Target<B> = HashMap<K, B, S>;
+
+    fn fmap_values<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_1_ref(&f)
+    }
+
+    fn try_fmap_values<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_1_ref(&f)
+    }
+}
+
+// Iterators keep their type, so the remaining items are mapped and collected like the collection they iterate over.
+
+impl<A: Eq + Hash> FunctorHashSet<A> for hash_set::IntoIter<A> {
+    type Target<B: Hash + Eq> = hash_set::IntoIter<B>;
+
+    fn __fmap_0_ref<B: Hash + Eq>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(f).collect::<HashSet<_>>().into_iter()
+    }
+
+    fn __try_fmap_0_ref<B: Hash + Eq, E>(
+        self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        self.map(f)
+            .collect::<Result<HashSet<_>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+
+    fn try_fmap_strict<B: Hash + Eq>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>> {
+        strict_hash_set(self.map(f), RandomState::new()).map(IntoIterator::into_iter)
+    }
+}
+
+impl<A: Eq + Hash, V> FunctorHashKeys<A> for hash_map::IntoIter<A, V> {
+    type Target<B: Hash + Eq> = hash_map::IntoIter<B, V>;
+    type Value = V;
+
+    fn __fmap_0_ref<B: Hash + Eq>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(|(k, v)| (f(k), v))
+            .collect::<HashMap<_, _>>()
+            .into_iter()
+    }
+
+    fn __try_fmap_0_ref<B: Hash + Eq, E>(
+        self,
+        f: &impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Target<B>, E> {
+        self.map(|(k, v)| f(k).map(|k| (k, v)))
+            .collect::<Result<HashMap<_, _>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+
+    fn __try_fmap_keys_merge<B: Hash + Eq, E>(
+        self,
+        f: &impl Fn(A) -> B,
+        merge: &impl Fn(B, V, V) -> Result<(B, V), E>,
+    ) -> Result<Self::Target<B>, E> {
+        merge_hash_map(self.map(|(k, v)| (f(k), v)), RandomState::new(), merge)
+            .map(IntoIterator::into_iter)
+    }
+}
+
+impl<K: Eq + Hash, A> Functor1<A> for hash_map::IntoIter<K, A> {
+    type Target<B> = hash_map::IntoIter<K, B>;
+
+    fn __fmap_1_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        self.map(|(k, v)| (k, f(v)))
+            .collect::<HashMap<_, _>>()
+            .into_iter()
+    }
+
+    fn __try_fmap_1_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.map(|(k, v)| f(v).map(|v| (k, v)))
+            .collect::<Result<HashMap<_, _>, E>>()
+            .map(IntoIterator::into_iter)
+    }
+}
+
+impl<K: Eq + Hash, A> Functor<A> for hash_map::IntoIter<K, A> {
+    type Target<B> = hash_map::IntoIter<K, B>;
+
+    /// By default HashMap iterators map their Value generic.
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+        self.__fmap_1_ref(&f)
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        self.__try_fmap_1_ref(&f)
+    }
+}
+
+family_impl!([K: Eq + Hash, T] hash_map::IntoIter<K, T> => hash_map::IntoIter<K, A>);
+
+impl<K: Eq + Hash, A> FunctorValues<A> for hash_map::IntoIter<K, A> {
+    type Target<B> = hash_map::IntoIter<K, B>;
 
     fn fmap_values<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
         self.__fmap_1_ref(&f)
@@ -118,3 +359,57 @@ impl<K: Eq + Hash, A> FunctorValues<A> for HashMap<K, A> {
         self.__try_fmap_1_ref(&f)
     }
 }
+
+functor_impl!(Mutex);
+family_impl!([T] Mutex<T> => Mutex<A>);
+
+/// A poisoned mutex is mapped anyway, the mapped mutex is not poisoned.
+impl<A> Functor0<A> for Mutex<A> {
+    type Target<B> = Mutex<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        Mutex::new(f(self.into_inner().unwrap_or_else(PoisonError::into_inner)))
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(self.into_inner().unwrap_or_else(PoisonError::into_inner)).map(Mutex::new)
+    }
+}
+
+functor_impl!(RwLock);
+family_impl!([T] RwLock<T> => RwLock<A>);
+
+/// A poisoned lock is mapped anyway, the mapped lock is not poisoned.
+impl<A> Functor0<A> for RwLock<A> {
+    type Target<B> = RwLock<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        RwLock::new(f(self.into_inner().unwrap_or_else(PoisonError::into_inner)))
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        f(self.into_inner().unwrap_or_else(PoisonError::into_inner)).map(RwLock::new)
+    }
+}
+
+functor_impl!(OnceLock);
+family_impl!([T] OnceLock<T> => OnceLock<A>);
+
+/// An uninitialized lock stays uninitialized, `f` is not called.
+impl<A> Functor0<A> for OnceLock<A> {
+    type Target<B> = OnceLock<B>;
+
+    fn __fmap_0_ref<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B> {
+        match self.into_inner() {
+            Some(v) => OnceLock::from(f(v)),
+            None => OnceLock::new(),
+        }
+    }
+
+    fn __try_fmap_0_ref<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+        match self.into_inner() {
+            Some(v) => f(v).map(OnceLock::from),
+            None => Ok(OnceLock::new()),
+        }
+    }
+}
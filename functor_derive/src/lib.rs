@@ -1,14 +1,30 @@
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(doctest)]
+mod diagnostics;
+#[cfg(feature = "alloc")]
 pub mod impl_alloc;
 pub mod impl_core;
+#[cfg(feature = "std")]
 pub mod impl_std;
 
+use core::convert::Infallible;
+use core::error::Error;
+use core::fmt::{self, Debug, Display, Formatter};
 use paste::paste;
 
 // Re-export derive macro.
 pub use functor_derive_lib::*;
+#[cfg(feature = "alloc")]
 pub use impl_alloc::*;
 #[allow(unused)]
 pub use impl_core::*;
+#[cfg(feature = "std")]
 pub use impl_std::*;
 
 pub trait Functor<A>: Sized {
@@ -19,6 +35,110 @@ pub trait Functor<A>: Sized {
     fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E>;
 }
 
+/// The family of types that differ only in the parameter their `Functor` maps, like `Vec<A>` for any `A`.
+/// Mapping a member gives another member of the same family, so generic code can chain mappings and name the results.
+///
+/// ```
+/// use functor_derive::FunctorFamily;
+///
+/// fn resolve_then_check<X>(x: X) -> X::Member<bool>
+/// where
+///     X: FunctorFamily<Member<&'static str> = X>,
+/// {
+///     let resolved = X::fmap_member(x, |name: &str| name.len());
+///     X::fmap_member(resolved, |len: usize| len > 3)
+/// }
+///
+/// assert_eq!(resolve_then_check(vec!["let", "match"]), vec![false, true]);
+/// assert_eq!(resolve_then_check(Some("fn")), Some(false));
+/// ```
+///
+/// Types whose members require bounds, like `Rc<A>` which needs `A: Clone`, are not families.
+pub trait FunctorFamily {
+    type Member<A>;
+
+    fn fmap_member<A, B>(x: Self::Member<A>, f: impl Fn(A) -> B) -> Self::Member<B>;
+
+    fn try_fmap_member<A, B, E>(
+        x: Self::Member<A>,
+        f: impl Fn(A) -> Result<B, E>,
+    ) -> Result<Self::Member<B>, E>;
+}
+
+/// Values that either hold an `Ok` value or a residual that short-circuits, like `Option<A>` and `Result<A, E>`.
+pub trait Fallible {
+    type Ok;
+    type Residual;
+    type Wrapped<B>;
+
+    fn into_result(self) -> Result<Self::Ok, Self::Residual>;
+
+    fn from_result<B>(result: Result<B, Self::Residual>) -> Self::Wrapped<B>;
+}
+
+impl<A> Fallible for Option<A> {
+    type Ok = A;
+    type Residual = ();
+    type Wrapped<B> = Option<B>;
+
+    fn into_result(self) -> Result<A, ()> {
+        self.ok_or(())
+    }
+
+    fn from_result<B>(result: Result<B, ()>) -> Option<B> {
+        result.ok()
+    }
+}
+
+impl<A, E> Fallible for Result<A, E> {
+    type Ok = A;
+    type Residual = E;
+    type Wrapped<B> = Result<B, E>;
+
+    fn into_result(self) -> Result<A, E> {
+        self
+    }
+
+    fn from_result<B>(result: Result<B, E>) -> Result<B, E> {
+        result
+    }
+}
+
+/// Turns a structure of `Option`s or `Result`s inside out, like `Vec<Option<A>>` into `Option<Vec<A>>`.
+/// Derived types provide the same `transpose` method when the `transpose` option is added.
+pub trait FunctorTranspose<A: Fallible>: Functor<A> {
+    /// Stops at the first `None` or `Err`, which is returned instead of the structure.
+    fn transpose(self) -> A::Wrapped<Self::Target<A::Ok>> {
+        A::from_result(self.try_fmap(A::into_result))
+    }
+}
+
+impl<A: Fallible, X: Functor<A>> FunctorTranspose<A> for X {}
+
+/// One step of the path to a value that a derived `from_fn` fills, which is passed as a slice of steps from the root.
+/// For example, `[Field("weights"), Index(2), Field("0")]` is the value at `self.weights[2].0`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SlotStep {
+    /// A field of a struct or an element of a tuple, named like `"weights"` or `"0"`.
+    Field(&'static str),
+    /// An element of an array.
+    Index(usize),
+}
+
+/// Provides the default of a field type `D` that a derived `from_fn` does not fill. It is implemented for every type
+/// whenever `D: Default`, so the bound `T: DefaultField<D>` names the parameter `T` and is only checked where `from_fn`
+/// is called, whereas a bound `D: Default` on a field type without generics would fail the derive if it does not hold.
+#[doc(hidden)]
+pub trait DefaultField<D> {
+    fn default_field() -> D;
+}
+
+impl<A: ?Sized, D: Default> DefaultField<D> for A {
+    fn default_field() -> D {
+        D::default()
+    }
+}
+
 #[macro_export]
 macro_rules! functor_n {
     ($n:expr) => {
@@ -27,6 +147,14 @@ macro_rules! functor_n {
         pub trait [<Functor $n>]<A>: Sized {
             type Target<B>;
 
+            fn [<fmap_ $n>]<B>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
+                self.[<__fmap_ $n _ref>](&f)
+            }
+
+            fn [<try_fmap_ $n>]<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E> {
+                self.[<__try_fmap_ $n _ref>](&f)
+            }
+
             fn [<__fmap_ $n _ref>]<B>(self, f: &impl Fn(A) -> B) -> Self::Target<B>;
 
             fn [<__try_fmap_ $n _ref>]<B, E>(self, f: &impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E>;
@@ -66,10 +194,38 @@ pub trait FunctorValues<A>: Sized {
     fn try_fmap_values<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Self::Target<B>, E>;
 }
 
+/// The mapping functions of types with `#[functor(lazy)]`, which are `Clone + 'static` as the adapters keep a copy.
+/// A type that is not lazy passes a borrowed function on to its fields, so it cannot hold a lazily mapped type.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot map a lazily mapped type, as it is not `Clone + 'static`",
+    label = "a lazily mapped type needs a `Clone + 'static` mapping function",
+    note = "fields of lazily mapped types require `#[functor(lazy)]` on the type that holds them"
+)]
+pub trait LazyMapper: Clone + 'static {}
+
+impl<F: Clone + 'static> LazyMapper for F {}
+
+/// Error returned when mapping the keys of a map or the elements of a set strictly, and two of them
+/// are mapped to the same `key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCollision<K> {
+    pub key: K,
+}
+
+impl<K> Display for KeyCollision<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "two keys were mapped to the same key")
+    }
+}
+
+impl<K: Debug> Error for KeyCollision<K> {}
+
 #[doc(hidden)]
 pub trait FunctorOrdKeys<A: Ord>: Sized {
     type Target<B: Ord>;
+    type Value;
 
+    /// When two keys are mapped to the same key, the value that comes last is kept.
     fn fmap_keys<B: Ord>(self, f: impl Fn(A) -> B) -> Self::Target<B> {
         self.__fmap_0_ref(&f)
     }
@@ -78,12 +234,52 @@ pub trait FunctorOrdKeys<A: Ord>: Sized {
         self.__try_fmap_0_ref(&f)
     }
 
+    /// When two keys are mapped to the same key, their values are combined with `merge`,
+    /// which is given the value that comes first as its first argument.
+    fn fmap_keys_with<B: Ord>(
+        self,
+        f: impl Fn(A) -> B,
+        merge: impl Fn(Self::Value, Self::Value) -> Self::Value,
+    ) -> Self::Target<B> {
+        match self.__try_fmap_keys_merge(&f, &|k, v1, v2| Ok::<_, Infallible>((k, merge(v1, v2)))) {
+            Ok(target) => target,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Fails with the colliding key when two keys are mapped to the same key.
+    fn try_fmap_keys_strict<B: Ord>(
+        self,
+        f: impl Fn(A) -> B,
+    ) -> Result<Self::Target<B>, KeyCollision<B>> {
+        self.__try_fmap_keys_merge(&f, &|key, _, _| Err(KeyCollision { key }))
+    }
+
     fn __fmap_0_ref<B: Ord>(self, f: &impl Fn(A) -> B) -> Self::Target<B>;
 
     fn __try_fmap_0_ref<B: Ord, E>(
         self,
         f: &impl Fn(A) -> Result<B, E>,
     ) -> Result<Self::Target<B>, E>;
+
+    fn __try_fmap_keys_merge<B: Ord, E>(
+        self,
+        f: &impl Fn(A) -> B,
+        merge: &impl Fn(B, Self::Value, Self::Value) -> Result<(B, Self::Value), E>,
+    ) -> Result<Self::Target<B>, E>;
+}
+
+#[doc(hidden)]
+pub trait FunctorOrdEntries<K: Ord, V>: Sized {
+    type Target<K2: Ord, V2>;
+
+    /// When two entries are mapped to the same key, the value that comes last is kept.
+    fn fmap_entries<K2: Ord, V2>(self, f: impl Fn(K, V) -> (K2, V2)) -> Self::Target<K2, V2>;
+
+    fn try_fmap_entries<K2: Ord, V2, E>(
+        self,
+        f: impl Fn(K, V) -> Result<(K2, V2), E>,
+    ) -> Result<Self::Target<K2, V2>, E>;
 }
 
 #[macro_export]
@@ -104,3 +300,25 @@ macro_rules! functor_impl {
         }
     };
 }
+
+/// Implements `FunctorFamily` for the generics `[...]` and type `$typ`, whose members `$member` are named using `A`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! family_impl {
+    ([$($generics:tt)*] $typ:ty => $member:ty) => {
+        impl<$($generics)*> $crate::FunctorFamily for $typ {
+            type Member<A> = $member;
+
+            fn fmap_member<A, B>(x: Self::Member<A>, f: impl Fn(A) -> B) -> Self::Member<B> {
+                $crate::Functor::fmap(x, f)
+            }
+
+            fn try_fmap_member<A, B, E>(
+                x: Self::Member<A>,
+                f: impl Fn(A) -> Result<B, E>,
+            ) -> Result<Self::Member<B>, E> {
+                $crate::Functor::try_fmap(x, f)
+            }
+        }
+    };
+}
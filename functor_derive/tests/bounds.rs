@@ -1,7 +1,10 @@
+#![cfg(feature = "std")]
 #![allow(unused_parens)]
 
 use functor_derive::Functor;
 use std::any::{Any, TypeId};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
 use std::fmt::Display;
 
 #[test]
@@ -54,3 +57,40 @@ fn trait_ignored() {
         TypeId::of::<StructSimple<u64, usize>>()
     );
 }
+
+#[test]
+fn inferred_key_bounds() {
+    #[derive(Functor)]
+    struct Index<T> {
+        by_key: HashMap<T, usize>,
+        sorted: Vec<BTreeSet<(T, u8)>>,
+        queue: BinaryHeap<Reverse<T>>,
+    }
+
+    let x = Index::<usize> {
+        by_key: HashMap::from([(1, 2)]),
+        sorted: vec![BTreeSet::from([(3, 4)])],
+        queue: BinaryHeap::from([Reverse(5)]),
+    };
+
+    let y = x.fmap(|x| x as u64);
+    assert_eq!(y.by_key, HashMap::from([(1u64, 2)]));
+    assert_eq!(y.sorted, vec![BTreeSet::from([(3u64, 4)])]);
+    assert_eq!(y.queue.into_vec(), vec![Reverse(5u64)]);
+}
+
+#[test]
+fn inferred_key_bounds_named() {
+    #[derive(Functor)]
+    #[functor(K as keys, V as values)]
+    struct Map<K, V> {
+        entries: HashMap<K, V>,
+    }
+
+    let x = Map {
+        entries: HashMap::from([(1usize, 2usize)]),
+    };
+
+    let y = x.fmap_keys(|x| x as u64).fmap_values(|x| x as u8);
+    assert_eq!(y.entries, HashMap::from([(1u64, 2u8)]));
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::{Functor, FunctorChoices};
+
+#[test]
+fn choices_vec() {
+    let x = vec![1, 2];
+    assert_eq!(
+        x.fmap_all_choices(|x| vec![x, x * 10]),
+        vec![vec![1, 2], vec![1, 20], vec![10, 2], vec![10, 20]]
+    );
+}
+
+#[test]
+fn choices_option_and_array() {
+    assert_eq!(
+        Some(1).fmap_all_choices(|x| vec![x, -x]),
+        vec![Some(1), Some(-1)]
+    );
+    assert_eq!(None::<i32>.fmap_all_choices(|x| vec![x, -x]), vec![None]);
+    assert_eq!(
+        [true, false].fmap_all_choices(|x| vec![x, !x]),
+        vec![[true, false], [true, true], [false, false], [false, true]]
+    );
+}
+
+#[test]
+fn choices_empty_alternatives() {
+    let x = vec![1, 2, 3];
+    assert!(x
+        .fmap_all_choices(|x| if x == 2 { vec![] } else { vec![x] })
+        .is_empty());
+
+    let x = Vec::<i32>::new();
+    assert_eq!(x.fmap_all_choices(|x| vec![x]), vec![Vec::<i32>::new()]);
+}
+
+#[test]
+fn choices_derived() {
+    #[derive(Functor, Clone, Debug, PartialEq)]
+    enum Expr<T> {
+        Var(T),
+        Add(Box<Expr<T>>, Box<Expr<T>>),
+        Const(i64),
+    }
+
+    let x = Expr::Add(
+        Box::new(Expr::Var("x")),
+        Box::new(Expr::Add(
+            Box::new(Expr::Var("y")),
+            Box::new(Expr::Const(1)),
+        )),
+    );
+
+    let cases = x.fmap_all_choices(|_| vec![0i64, 1]);
+    assert_eq!(cases.len(), 4);
+    assert_eq!(
+        cases[1],
+        Expr::Add(
+            Box::new(Expr::Var(0)),
+            Box::new(Expr::Add(Box::new(Expr::Var(1)), Box::new(Expr::Const(1)))),
+        )
+    );
+}
+
+#[test]
+fn choices_lazy() {
+    // The full product has 2^64 elements, which is never materialised.
+    let x = vec![0u8; 64];
+    let mut cases = x.fmap_all_choices_iter(|x| vec![x, 1]);
+
+    assert_eq!(cases.next(), Some(vec![0; 64]));
+    let mut second = vec![0; 64];
+    second[63] = 1;
+    assert_eq!(cases.next(), Some(second));
+}
+
+#[test]
+fn choices_option() {
+    // Types with lifetime parameters are no functor family, so their choices are generated.
+    #[derive(Functor, Clone, Debug, PartialEq)]
+    #[functor(choices)]
+    struct Labelled<'a, T> {
+        label: &'a str,
+        values: Vec<T>,
+    }
+
+    let x = Labelled {
+        label: "x",
+        values: vec![1, 2],
+    };
+    let cases = x.fmap_all_choices(|x| vec![x, -x]);
+    assert_eq!(cases.len(), 4);
+    assert_eq!(
+        cases[2],
+        Labelled {
+            label: "x",
+            values: vec![-1, 2],
+        }
+    );
+}
+
+#[test]
+fn choices_option_named() {
+    #[derive(Functor, Clone, Debug, PartialEq)]
+    #[functor(K as keys, V as values, choices)]
+    struct Entry<'a, K, V> {
+        key: K,
+        value: V,
+        note: &'a str,
+    }
+
+    let x = Entry {
+        key: 1,
+        value: 'a',
+        note: "x",
+    };
+    let mut cases = x
+        .clone()
+        .fmap_all_choices_iter_values(|v| vec![v, v.to_ascii_uppercase()]);
+    assert_eq!(cases.nth(1).map(|entry| entry.value), Some('A'));
+    assert_eq!(x.fmap_all_choices_keys(|k| vec![k, k + 1]).len(), 2);
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "std")]
+
+use functor_derive::{Functor, FunctorFamily};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+struct Resolved(usize);
+
+#[derive(Debug, PartialEq)]
+struct Typed(bool);
+
+/// A generic pass that chains two mappings over any container of the same family.
+fn resolve_then_typecheck<X>(x: X) -> X::Member<Typed>
+where
+    X: FunctorFamily<Member<&'static str> = X>,
+{
+    let resolved = X::fmap_member(x, |name: &str| Resolved(name.len()));
+    X::fmap_member(resolved, |Resolved(len)| Typed(len > 3))
+}
+
+fn try_resolve<X>(x: X) -> Result<X::Member<u64>, std::num::ParseIntError>
+where
+    X: FunctorFamily<Member<&'static str> = X>,
+{
+    let trimmed = X::fmap_member(x, |s: &'static str| s.trim());
+    X::try_fmap_member(trimmed, |s: &str| s.parse::<u64>())
+}
+
+#[test]
+fn family_std() {
+    assert_eq!(
+        resolve_then_typecheck(vec!["let", "match"]),
+        vec![Typed(false), Typed(true)]
+    );
+    assert_eq!(resolve_then_typecheck(Some("loop")), Some(Typed(true)));
+    assert_eq!(resolve_then_typecheck(Ok::<_, ()>("fn")), Ok(Typed(false)));
+    assert_eq!(resolve_then_typecheck((1, "while")), (1, Typed(true)));
+    assert_eq!(
+        resolve_then_typecheck(HashMap::from([(1, "if")])),
+        HashMap::from([(1, Typed(false))])
+    );
+}
+
+#[test]
+fn family_derived() {
+    #[derive(Functor, Debug, PartialEq)]
+    struct Block<T, S> {
+        statements: Vec<T>,
+        span: S,
+    }
+
+    #[derive(Functor, Debug, PartialEq)]
+    enum Expr<T> {
+        Leaf(T),
+        Pair(Box<Expr<T>>, Box<Expr<T>>),
+    }
+
+    let x = Block {
+        statements: vec!["return"],
+        span: (0, 6),
+    };
+    assert_eq!(
+        resolve_then_typecheck(x),
+        Block {
+            statements: vec![Typed(true)],
+            span: (0, 6),
+        }
+    );
+
+    let x = Expr::Pair(Box::new(Expr::Leaf("a")), Box::new(Expr::Leaf("break")));
+    assert_eq!(
+        resolve_then_typecheck(x),
+        Expr::Pair(
+            Box::new(Expr::Leaf(Typed(false))),
+            Box::new(Expr::Leaf(Typed(true)))
+        )
+    );
+}
+
+#[test]
+fn family_try() {
+    assert_eq!(try_resolve(vec![" 1", "2 "]), Ok(vec![1, 2]));
+    assert!(try_resolve([" 1", "two"]).is_err());
+}
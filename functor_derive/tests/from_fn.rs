@@ -0,0 +1,89 @@
+use functor_derive::{Functor, SlotStep};
+
+#[derive(Functor, Debug, PartialEq)]
+#[functor(from_fn)]
+struct Layer<T> {
+    weights: [[T; 2]; 2],
+    bias: (T, T),
+    name: String,
+}
+
+#[test]
+fn from_fn_paths() {
+    let mut paths = Vec::new();
+    let x = Layer::from_fn(|slot| {
+        paths.push(slot.to_vec());
+        paths.len()
+    });
+
+    assert_eq!(
+        x,
+        Layer {
+            weights: [[1, 2], [3, 4]],
+            bias: (5, 6),
+            name: String::new(),
+        }
+    );
+    assert_eq!(
+        paths[2],
+        [
+            SlotStep::Field("weights"),
+            SlotStep::Index(1),
+            SlotStep::Index(0)
+        ]
+    );
+    assert_eq!(paths[5], [SlotStep::Field("bias"), SlotStep::Field("1")]);
+}
+
+#[test]
+fn replicate() {
+    let zero = Layer::replicate(0.0);
+    assert_eq!(zero.weights, [[0.0; 2]; 2]);
+    assert_eq!(zero.bias, (0.0, 0.0));
+
+    // Combined with `fmap`, this builds a structure of the same shape from another.
+    let ones = zero.fmap(|x: f64| x + 1.0);
+    assert_eq!(ones.bias, (1.0, 1.0));
+}
+
+#[test]
+fn from_fn_named() {
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(T as weights, S as labels, from_fn)]
+    struct Tagged<T, S>(T, [S; 3]);
+
+    assert_eq!(
+        Tagged::<u8, &str>::replicate_labels("x"),
+        Tagged(0, ["x", "x", "x"])
+    );
+    assert_eq!(
+        Tagged::<u8, u8>::from_fn_weights(|slot| (slot == [SlotStep::Field("0")]) as u8),
+        Tagged(1, [0, 0, 0])
+    );
+}
+
+#[test]
+fn from_fn_non_default_field() {
+    // `Id` is not `Default`, which only makes `from_fn` unavailable instead of failing the derive.
+    #[derive(Debug, PartialEq)]
+    struct Id(u32);
+
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(from_fn)]
+    struct Named<T> {
+        value: T,
+        id: Id,
+    }
+
+    let x = Named {
+        value: 1,
+        id: Id(7),
+    };
+    assert_eq!(
+        x.fmap(|x| x + 1),
+        Named {
+            value: 2,
+            id: Id(7),
+        }
+    );
+}
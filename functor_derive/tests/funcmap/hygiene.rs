@@ -85,3 +85,49 @@ fn raw_identifiers_are_supported() {
         r#let,
     }
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn prelude_items_may_be_shadowed() {
+    #[allow(dead_code)]
+    mod shadowed {
+        pub struct Some;
+        pub struct None;
+        pub struct Ok;
+        pub struct Err;
+        pub struct Result;
+    }
+
+    use shadowed::*;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    fn combine(first: usize, second: usize) -> usize {
+        first + second
+    }
+
+    #[derive(Functor)]
+    #[functor(collisions = strict)]
+    struct Strict<T: Ord> {
+        map: BTreeMap<T, usize>,
+        set: BTreeSet<T>,
+    }
+
+    #[derive(Functor)]
+    #[functor(collisions = merge via combine)]
+    struct Merged<T: Ord> {
+        map: BTreeMap<T, usize>,
+    }
+
+    #[derive(Functor)]
+    #[functor(stack_safe)]
+    struct List<T> {
+        value: T,
+        next: Option<Box<List<T>>>,
+    }
+
+    #[derive(Functor)]
+    #[functor(lazy)]
+    struct Pending<T> {
+        items: Box<dyn Iterator<Item = T>>,
+    }
+}
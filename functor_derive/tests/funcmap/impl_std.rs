@@ -1,7 +1,7 @@
 use crate::funcmap::{T1, T2};
 use functor_derive::*;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map, hash_set, HashMap, HashSet},
     hash::Hash,
 };
 
@@ -27,28 +27,27 @@ fn field_of_hash_map_type_is_mapped_over_value() {
     assert_eq!(dst, Test([((), T2)].into()));
 }
 
-// We don't have iterators in our std implementations.
-// #[test]
-// fn field_of_hash_map_into_iter_type_is_mapped_over_key() {
-//     // #[derive(Functor, Debug)]
-//     struct Test<T>(hash_map::IntoIter<T, ()>);
-//
-//     let src = Test(HashMap::from([(T1, ())]).into_iter());
-//     let dst = src.fmap(|_| T2);
-//
-//     assert_eq!(dst.0.collect::<Vec<_>>(), [(T2, ())]);
-// }
-//
-// #[test]
-// fn field_of_hash_map_into_iter_type_is_mapped_over_value() {
-//     #[derive(Functor, Debug)]
-//     struct Test<T>(hash_map::IntoIter<(), T>);
-//
-//     let src = Test(HashMap::from([((), T1)]).into_iter());
-//     let dst = src.fmap(|_| T2);
-//
-//     assert_eq!(dst.0.collect::<Vec<_>>(), [((), T2)]);
-// }
+#[test]
+fn field_of_hash_map_into_iter_type_is_mapped_over_key() {
+    #[derive(Functor, Debug)]
+    struct Test<T: Eq + Hash>(hash_map::IntoIter<T, ()>);
+
+    let src = Test(HashMap::from([(T1, ())]).into_iter());
+    let dst = src.fmap(|_| T2);
+
+    assert_eq!(dst.0.collect::<Vec<_>>(), [(T2, ())]);
+}
+
+#[test]
+fn field_of_hash_map_into_iter_type_is_mapped_over_value() {
+    #[derive(Functor, Debug)]
+    struct Test<T>(hash_map::IntoIter<(), T>);
+
+    let src = Test(HashMap::from([((), T1)]).into_iter());
+    let dst = src.fmap(|_| T2);
+
+    assert_eq!(dst.0.collect::<Vec<_>>(), [((), T2)]);
+}
 
 #[test]
 fn field_of_hash_set_type_is_mapped() {
@@ -61,14 +60,13 @@ fn field_of_hash_set_type_is_mapped() {
     assert_eq!(dst, Test([T2].into()));
 }
 
-// We don't have iterators in our std implementations.
-// #[test]
-// fn field_of_hash_set_into_iter_type_is_mapped() {
-//     #[derive(Functor, Debug)]
-//     struct Test<T>(hash_set::IntoIter<T>);
-//
-//     let src = Test(HashSet::from([T1]).into_iter());
-//     let dst = src.fmap(|_| T2);
-//
-//     assert_eq!(dst.0.collect::<Vec<_>>(), [T2]);
-// }
+#[test]
+fn field_of_hash_set_into_iter_type_is_mapped() {
+    #[derive(Functor, Debug)]
+    struct Test<T: Eq + Hash>(hash_set::IntoIter<T>);
+
+    let src = Test(HashSet::from([T1]).into_iter());
+    let dst = src.fmap(|_| T2);
+
+    assert_eq!(dst.0.collect::<Vec<_>>(), [T2]);
+}
@@ -5,6 +5,7 @@
 //! Licensed under either of
 //! * Apache License, Version 2.0 (LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0)
 //! * MIT license (LICENSE-MIT or https://opensource.org/licenses/MIT)
+//!
 //! at your option.
 
 #![allow(unused)]
@@ -12,8 +13,10 @@
 mod decorations;
 mod fallible;
 mod hygiene;
+#[cfg(feature = "alloc")]
 mod impl_alloc;
 mod impl_core;
+#[cfg(feature = "std")]
 mod impl_std;
 mod lints;
 mod multi_param;
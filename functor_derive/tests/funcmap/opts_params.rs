@@ -5,6 +5,7 @@ use functor_derive_lib::Functor;
 fn generics_to_be_mapped_can_be_configured() {
     fn noop() {}
 
+    #[allow(unpredictable_function_pointer_comparisons)]
     #[derive(Functor, Debug, PartialEq)]
     #[functor(S as s, U as u)]
     struct Test<S, T, U> {
@@ -0,0 +1,161 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::Functor;
+use std::any::{Any, TypeId};
+
+/// Implements only the public trait, like a type from a downstream crate would.
+struct Wrapper<A>(A);
+
+impl<A> Functor<A> for Wrapper<A> {
+    type Target<B> = Wrapper<B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Wrapper<B> {
+        Wrapper(f(self.0))
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Wrapper<B>, E> {
+        Ok(Wrapper(f(self.0)?))
+    }
+}
+
+/// Implements the public trait over its second parameter.
+struct Tagged<Tag, A> {
+    tag: Tag,
+    value: A,
+}
+
+impl<Tag, A> Functor<A> for Tagged<Tag, A> {
+    type Target<B> = Tagged<Tag, B>;
+
+    fn fmap<B>(self, f: impl Fn(A) -> B) -> Tagged<Tag, B> {
+        Tagged {
+            tag: self.tag,
+            value: f(self.value),
+        }
+    }
+
+    fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Tagged<Tag, B>, E> {
+        Ok(Tagged {
+            tag: self.tag,
+            value: f(self.value)?,
+        })
+    }
+}
+
+#[test]
+fn functor_trait_fields() {
+    #[derive(Functor)]
+    #[functor(Wrapper: Functor, Tagged: functor_derive::Functor)]
+    struct MyType<T> {
+        single: Wrapper<T>,
+        list: Vec<Wrapper<T>>,
+        tagged: Tagged<&'static str, Option<T>>,
+    }
+
+    let x = MyType {
+        single: Wrapper(1usize),
+        list: vec![Wrapper(2), Wrapper(3)],
+        tagged: Tagged {
+            tag: "four",
+            value: Some(4),
+        },
+    };
+
+    let y = x.fmap(|x| x as u64 * 10);
+
+    assert_eq!(y.type_id(), TypeId::of::<MyType<u64>>());
+    assert_eq!(y.single.0, 10);
+    assert_eq!(y.list.iter().map(|x| x.0).collect::<Vec<_>>(), [20, 30]);
+    assert_eq!(y.tagged.tag, "four");
+    assert_eq!(y.tagged.value, Some(40));
+}
+
+#[test]
+fn functor_trait_fields_try() {
+    #[derive(Functor)]
+    #[functor(Wrapper: Functor)]
+    struct MyType<T> {
+        list: Vec<Wrapper<T>>,
+    }
+
+    let x = MyType {
+        list: vec![Wrapper("1"), Wrapper("2")],
+    };
+    let y = x.try_fmap(|x| x.parse::<u64>()).unwrap();
+    assert_eq!(y.list.iter().map(|x| x.0).collect::<Vec<_>>(), [1, 2]);
+
+    let x = MyType {
+        list: vec![Wrapper("1"), Wrapper("two")],
+    };
+    assert!(x.try_fmap(|x| x.parse::<u64>()).is_err());
+}
+
+/// Shares its name with the type of the other module, but implements only the public trait.
+mod by_hand {
+    use functor_derive::Functor;
+
+    pub struct Wrapper<A>(pub A);
+
+    impl<A> Functor<A> for Wrapper<A> {
+        type Target<B> = Wrapper<B>;
+
+        fn fmap<B>(self, f: impl Fn(A) -> B) -> Wrapper<B> {
+            Wrapper(f(self.0))
+        }
+
+        fn try_fmap<B, E>(self, f: impl Fn(A) -> Result<B, E>) -> Result<Wrapper<B>, E> {
+            Ok(Wrapper(f(self.0)?))
+        }
+    }
+}
+
+mod derived {
+    use functor_derive::Functor;
+
+    #[derive(Functor)]
+    pub struct Wrapper<A>(pub A);
+}
+
+#[test]
+fn functor_trait_same_name() {
+    #[derive(Functor)]
+    #[functor(by_hand::Wrapper: Functor)]
+    struct MyType<T> {
+        by_hand: by_hand::Wrapper<T>,
+        derived: derived::Wrapper<T>,
+    }
+
+    let x = MyType {
+        by_hand: by_hand::Wrapper(1usize),
+        derived: derived::Wrapper(2),
+    };
+
+    let y = x.fmap(|x| x as u64 * 10);
+
+    assert_eq!(y.by_hand.0, 10);
+    assert_eq!(y.derived.0, 20);
+}
+
+#[test]
+fn functor_trait_arguments() {
+    #[derive(Functor)]
+    #[functor(Tagged<u8, T>: Functor)]
+    struct MyType<T> {
+        tagged: Tagged<u8, T>,
+        derived: derived::Wrapper<T>,
+    }
+
+    let x = MyType {
+        tagged: Tagged {
+            tag: 3,
+            value: 1usize,
+        },
+        derived: derived::Wrapper(2),
+    };
+
+    let y = x.fmap(|x| x as u64 * 10);
+
+    assert_eq!(y.tagged.tag, 3);
+    assert_eq!(y.tagged.value, 10);
+    assert_eq!(y.derived.0, 20);
+}
@@ -0,0 +1,338 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::Functor;
+
+/// An enum the size of a real intermediate representation, which keeps the size of the derived code in check.
+#[derive(Functor, Debug, PartialEq)]
+#[functor(T as values, S as spans)]
+enum Instr<T, S> {
+    Op0(T, S),
+    Op1 { args: Vec<T>, span: S },
+    Op2(Option<Box<Instr<T, S>>>, usize),
+    Op3(T, S),
+    Op4 { args: Vec<T>, span: S },
+    Op5(Option<Box<Instr<T, S>>>, usize),
+    Op6(T, S),
+    Op7 { args: Vec<T>, span: S },
+    Op8(Option<Box<Instr<T, S>>>, usize),
+    Op9(T, S),
+    Op10 { args: Vec<T>, span: S },
+    Op11(Option<Box<Instr<T, S>>>, usize),
+    Op12(T, S),
+    Op13 { args: Vec<T>, span: S },
+    Op14(Option<Box<Instr<T, S>>>, usize),
+    Op15(T, S),
+    Op16 { args: Vec<T>, span: S },
+    Op17(Option<Box<Instr<T, S>>>, usize),
+    Op18(T, S),
+    Op19 { args: Vec<T>, span: S },
+    Op20(Option<Box<Instr<T, S>>>, usize),
+    Op21(T, S),
+    Op22 { args: Vec<T>, span: S },
+    Op23(Option<Box<Instr<T, S>>>, usize),
+    Op24(T, S),
+    Op25 { args: Vec<T>, span: S },
+    Op26(Option<Box<Instr<T, S>>>, usize),
+    Op27(T, S),
+    Op28 { args: Vec<T>, span: S },
+    Op29(Option<Box<Instr<T, S>>>, usize),
+    Op30(T, S),
+    Op31 { args: Vec<T>, span: S },
+    Op32(Option<Box<Instr<T, S>>>, usize),
+    Op33(T, S),
+    Op34 { args: Vec<T>, span: S },
+    Op35(Option<Box<Instr<T, S>>>, usize),
+    Op36(T, S),
+    Op37 { args: Vec<T>, span: S },
+    Op38(Option<Box<Instr<T, S>>>, usize),
+    Op39(T, S),
+    Op40 { args: Vec<T>, span: S },
+    Op41(Option<Box<Instr<T, S>>>, usize),
+    Op42(T, S),
+    Op43 { args: Vec<T>, span: S },
+    Op44(Option<Box<Instr<T, S>>>, usize),
+    Op45(T, S),
+    Op46 { args: Vec<T>, span: S },
+    Op47(Option<Box<Instr<T, S>>>, usize),
+    Op48(T, S),
+    Op49 { args: Vec<T>, span: S },
+    Op50(Option<Box<Instr<T, S>>>, usize),
+    Op51(T, S),
+    Op52 { args: Vec<T>, span: S },
+    Op53(Option<Box<Instr<T, S>>>, usize),
+    Op54(T, S),
+    Op55 { args: Vec<T>, span: S },
+    Op56(Option<Box<Instr<T, S>>>, usize),
+    Op57(T, S),
+    Op58 { args: Vec<T>, span: S },
+    Op59(Option<Box<Instr<T, S>>>, usize),
+    Op60(T, S),
+    Op61 { args: Vec<T>, span: S },
+    Op62(Option<Box<Instr<T, S>>>, usize),
+    Op63(T, S),
+    Op64 { args: Vec<T>, span: S },
+    Op65(Option<Box<Instr<T, S>>>, usize),
+    Op66(T, S),
+    Op67 { args: Vec<T>, span: S },
+    Op68(Option<Box<Instr<T, S>>>, usize),
+    Op69(T, S),
+    Op70 { args: Vec<T>, span: S },
+    Op71(Option<Box<Instr<T, S>>>, usize),
+    Op72(T, S),
+    Op73 { args: Vec<T>, span: S },
+    Op74(Option<Box<Instr<T, S>>>, usize),
+    Op75(T, S),
+    Op76 { args: Vec<T>, span: S },
+    Op77(Option<Box<Instr<T, S>>>, usize),
+    Op78(T, S),
+    Op79 { args: Vec<T>, span: S },
+    Op80(Option<Box<Instr<T, S>>>, usize),
+    Op81(T, S),
+    Op82 { args: Vec<T>, span: S },
+    Op83(Option<Box<Instr<T, S>>>, usize),
+    Op84(T, S),
+    Op85 { args: Vec<T>, span: S },
+    Op86(Option<Box<Instr<T, S>>>, usize),
+    Op87(T, S),
+    Op88 { args: Vec<T>, span: S },
+    Op89(Option<Box<Instr<T, S>>>, usize),
+    Op90(T, S),
+    Op91 { args: Vec<T>, span: S },
+    Op92(Option<Box<Instr<T, S>>>, usize),
+    Op93(T, S),
+    Op94 { args: Vec<T>, span: S },
+    Op95(Option<Box<Instr<T, S>>>, usize),
+    Op96(T, S),
+    Op97 { args: Vec<T>, span: S },
+    Op98(Option<Box<Instr<T, S>>>, usize),
+    Op99(T, S),
+    Op100 { args: Vec<T>, span: S },
+    Op101(Option<Box<Instr<T, S>>>, usize),
+    Op102(T, S),
+    Op103 { args: Vec<T>, span: S },
+    Op104(Option<Box<Instr<T, S>>>, usize),
+    Op105(T, S),
+    Op106 { args: Vec<T>, span: S },
+    Op107(Option<Box<Instr<T, S>>>, usize),
+    Op108(T, S),
+    Op109 { args: Vec<T>, span: S },
+    Op110(Option<Box<Instr<T, S>>>, usize),
+    Op111(T, S),
+    Op112 { args: Vec<T>, span: S },
+    Op113(Option<Box<Instr<T, S>>>, usize),
+    Op114(T, S),
+    Op115 { args: Vec<T>, span: S },
+    Op116(Option<Box<Instr<T, S>>>, usize),
+    Op117(T, S),
+    Op118 { args: Vec<T>, span: S },
+    Op119(Option<Box<Instr<T, S>>>, usize),
+    Op120(T, S),
+    Op121 { args: Vec<T>, span: S },
+    Op122(Option<Box<Instr<T, S>>>, usize),
+    Op123(T, S),
+    Op124 { args: Vec<T>, span: S },
+    Op125(Option<Box<Instr<T, S>>>, usize),
+    Op126(T, S),
+    Op127 { args: Vec<T>, span: S },
+    Op128(Option<Box<Instr<T, S>>>, usize),
+    Op129(T, S),
+    Op130 { args: Vec<T>, span: S },
+    Op131(Option<Box<Instr<T, S>>>, usize),
+    Op132(T, S),
+    Op133 { args: Vec<T>, span: S },
+    Op134(Option<Box<Instr<T, S>>>, usize),
+    Op135(T, S),
+    Op136 { args: Vec<T>, span: S },
+    Op137(Option<Box<Instr<T, S>>>, usize),
+    Op138(T, S),
+    Op139 { args: Vec<T>, span: S },
+    Op140(Option<Box<Instr<T, S>>>, usize),
+    Op141(T, S),
+    Op142 { args: Vec<T>, span: S },
+    Op143(Option<Box<Instr<T, S>>>, usize),
+    Op144(T, S),
+    Op145 { args: Vec<T>, span: S },
+    Op146(Option<Box<Instr<T, S>>>, usize),
+    Op147(T, S),
+    Op148 { args: Vec<T>, span: S },
+    Op149(Option<Box<Instr<T, S>>>, usize),
+    Op150(T, S),
+    Op151 { args: Vec<T>, span: S },
+    Op152(Option<Box<Instr<T, S>>>, usize),
+    Op153(T, S),
+    Op154 { args: Vec<T>, span: S },
+    Op155(Option<Box<Instr<T, S>>>, usize),
+    Op156(T, S),
+    Op157 { args: Vec<T>, span: S },
+    Op158(Option<Box<Instr<T, S>>>, usize),
+    Op159(T, S),
+    Op160 { args: Vec<T>, span: S },
+    Op161(Option<Box<Instr<T, S>>>, usize),
+    Op162(T, S),
+    Op163 { args: Vec<T>, span: S },
+    Op164(Option<Box<Instr<T, S>>>, usize),
+    Op165(T, S),
+    Op166 { args: Vec<T>, span: S },
+    Op167(Option<Box<Instr<T, S>>>, usize),
+    Op168(T, S),
+    Op169 { args: Vec<T>, span: S },
+    Op170(Option<Box<Instr<T, S>>>, usize),
+    Op171(T, S),
+    Op172 { args: Vec<T>, span: S },
+    Op173(Option<Box<Instr<T, S>>>, usize),
+    Op174(T, S),
+    Op175 { args: Vec<T>, span: S },
+    Op176(Option<Box<Instr<T, S>>>, usize),
+    Op177(T, S),
+    Op178 { args: Vec<T>, span: S },
+    Op179(Option<Box<Instr<T, S>>>, usize),
+    Op180(T, S),
+    Op181 { args: Vec<T>, span: S },
+    Op182(Option<Box<Instr<T, S>>>, usize),
+    Op183(T, S),
+    Op184 { args: Vec<T>, span: S },
+    Op185(Option<Box<Instr<T, S>>>, usize),
+    Op186(T, S),
+    Op187 { args: Vec<T>, span: S },
+    Op188(Option<Box<Instr<T, S>>>, usize),
+    Op189(T, S),
+    Op190 { args: Vec<T>, span: S },
+    Op191(Option<Box<Instr<T, S>>>, usize),
+    Op192(T, S),
+    Op193 { args: Vec<T>, span: S },
+    Op194(Option<Box<Instr<T, S>>>, usize),
+    Op195(T, S),
+    Op196 { args: Vec<T>, span: S },
+    Op197(Option<Box<Instr<T, S>>>, usize),
+    Op198(T, S),
+    Op199 { args: Vec<T>, span: S },
+    Op200(Option<Box<Instr<T, S>>>, usize),
+    Op201(T, S),
+    Op202 { args: Vec<T>, span: S },
+    Op203(Option<Box<Instr<T, S>>>, usize),
+    Op204(T, S),
+    Op205 { args: Vec<T>, span: S },
+    Op206(Option<Box<Instr<T, S>>>, usize),
+    Op207(T, S),
+    Op208 { args: Vec<T>, span: S },
+    Op209(Option<Box<Instr<T, S>>>, usize),
+    Op210(T, S),
+    Op211 { args: Vec<T>, span: S },
+    Op212(Option<Box<Instr<T, S>>>, usize),
+    Op213(T, S),
+    Op214 { args: Vec<T>, span: S },
+    Op215(Option<Box<Instr<T, S>>>, usize),
+    Op216(T, S),
+    Op217 { args: Vec<T>, span: S },
+    Op218(Option<Box<Instr<T, S>>>, usize),
+    Op219(T, S),
+    Op220 { args: Vec<T>, span: S },
+    Op221(Option<Box<Instr<T, S>>>, usize),
+    Op222(T, S),
+    Op223 { args: Vec<T>, span: S },
+    Op224(Option<Box<Instr<T, S>>>, usize),
+    Op225(T, S),
+    Op226 { args: Vec<T>, span: S },
+    Op227(Option<Box<Instr<T, S>>>, usize),
+    Op228(T, S),
+    Op229 { args: Vec<T>, span: S },
+    Op230(Option<Box<Instr<T, S>>>, usize),
+    Op231(T, S),
+    Op232 { args: Vec<T>, span: S },
+    Op233(Option<Box<Instr<T, S>>>, usize),
+    Op234(T, S),
+    Op235 { args: Vec<T>, span: S },
+    Op236(Option<Box<Instr<T, S>>>, usize),
+    Op237(T, S),
+    Op238 { args: Vec<T>, span: S },
+    Op239(Option<Box<Instr<T, S>>>, usize),
+    Op240(T, S),
+    Op241 { args: Vec<T>, span: S },
+    Op242(Option<Box<Instr<T, S>>>, usize),
+    Op243(T, S),
+    Op244 { args: Vec<T>, span: S },
+    Op245(Option<Box<Instr<T, S>>>, usize),
+    Op246(T, S),
+    Op247 { args: Vec<T>, span: S },
+    Op248(Option<Box<Instr<T, S>>>, usize),
+    Op249(T, S),
+    Op250 { args: Vec<T>, span: S },
+    Op251(Option<Box<Instr<T, S>>>, usize),
+    Op252(T, S),
+    Op253 { args: Vec<T>, span: S },
+    Op254(Option<Box<Instr<T, S>>>, usize),
+    Op255(T, S),
+    Op256 { args: Vec<T>, span: S },
+    Op257(Option<Box<Instr<T, S>>>, usize),
+    Op258(T, S),
+    Op259 { args: Vec<T>, span: S },
+    Op260(Option<Box<Instr<T, S>>>, usize),
+    Op261(T, S),
+    Op262 { args: Vec<T>, span: S },
+    Op263(Option<Box<Instr<T, S>>>, usize),
+    Op264(T, S),
+    Op265 { args: Vec<T>, span: S },
+    Op266(Option<Box<Instr<T, S>>>, usize),
+    Op267(T, S),
+    Op268 { args: Vec<T>, span: S },
+    Op269(Option<Box<Instr<T, S>>>, usize),
+    Op270(T, S),
+    Op271 { args: Vec<T>, span: S },
+    Op272(Option<Box<Instr<T, S>>>, usize),
+    Op273(T, S),
+    Op274 { args: Vec<T>, span: S },
+    Op275(Option<Box<Instr<T, S>>>, usize),
+    Op276(T, S),
+    Op277 { args: Vec<T>, span: S },
+    Op278(Option<Box<Instr<T, S>>>, usize),
+    Op279(T, S),
+    Op280 { args: Vec<T>, span: S },
+    Op281(Option<Box<Instr<T, S>>>, usize),
+    Op282(T, S),
+    Op283 { args: Vec<T>, span: S },
+    Op284(Option<Box<Instr<T, S>>>, usize),
+    Op285(T, S),
+    Op286 { args: Vec<T>, span: S },
+    Op287(Option<Box<Instr<T, S>>>, usize),
+    Op288(T, S),
+    Op289 { args: Vec<T>, span: S },
+    Op290(Option<Box<Instr<T, S>>>, usize),
+    Op291(T, S),
+    Op292 { args: Vec<T>, span: S },
+    Op293(Option<Box<Instr<T, S>>>, usize),
+    Op294(T, S),
+    Op295 { args: Vec<T>, span: S },
+    Op296(Option<Box<Instr<T, S>>>, usize),
+    Op297(T, S),
+    Op298 { args: Vec<T>, span: S },
+    Op299(Option<Box<Instr<T, S>>>, usize),
+}
+
+#[test]
+fn large_enum() {
+    let x = Instr::Op2(
+        Some(Box::new(Instr::Op298 {
+            args: vec![1, 2],
+            span: 0..1,
+        })),
+        3,
+    );
+    assert_eq!(
+        x.fmap_values(|v| v * 10).fmap_spans(|span| span.len()),
+        Instr::Op2(
+            Some(Box::new(Instr::Op298 {
+                args: vec![10, 20],
+                span: 1
+            })),
+            3
+        )
+    );
+
+    let x = Instr::Op0("1", "span");
+    assert_eq!(
+        x.try_fmap_values(|v| v.parse::<u8>()),
+        Ok(Instr::Op0(1, "span"))
+    );
+    let x = Instr::Op0::<_, ()>("one", ());
+    assert!(x.try_fmap_values(|v| v.parse::<u8>()).is_err());
+}
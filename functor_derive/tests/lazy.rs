@@ -0,0 +1,174 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::Functor;
+use std::any::{Any, TypeId};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn boxed_iterator() {
+    #[derive(Functor)]
+    #[functor(lazy)]
+    struct Pending<T> {
+        items: Box<dyn Iterator<Item = T>>,
+    }
+
+    let x = Pending::<usize> {
+        items: Box::new(vec![1, 2, 3].into_iter()),
+    };
+
+    let y = x.fmap(|x| x as u64 * 2);
+    assert_eq!(y.type_id(), TypeId::of::<Pending<u64>>());
+    assert_eq!(y.items.collect::<Vec<_>>(), vec![2, 4, 6]);
+}
+
+#[test]
+fn boxed_iterator_is_lazy() {
+    #[derive(Functor)]
+    #[functor(lazy)]
+    struct Pending<T> {
+        items: Box<dyn Iterator<Item = T>>,
+    }
+
+    let calls = Rc::new(Cell::new(0));
+    let x = Pending::<usize> {
+        items: Box::new(0..),
+    };
+
+    let counter = calls.clone();
+    let mut y = x.fmap(move |x| {
+        counter.set(counter.get() + 1);
+        x + 1
+    });
+    assert_eq!(calls.get(), 0);
+
+    assert_eq!(y.items.next(), Some(1));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn boxed_iterator_nested() {
+    #[derive(Functor)]
+    #[functor(lazy)]
+    enum Stream<T> {
+        Items(
+            Option<Box<dyn DoubleEndedIterator<Item = Vec<T>> + Send>>,
+            T,
+        ),
+        Done,
+    }
+
+    let x = Stream::Items(
+        Some(Box::new(vec![vec![1usize], vec![2, 3]].into_iter())),
+        4,
+    );
+
+    let Stream::Items(Some(items), last) = x.fmap(|x| x.to_string()) else {
+        panic!()
+    };
+    assert_eq!(last, "4");
+    assert_eq!(
+        items.rev().collect::<Vec<_>>(),
+        vec![
+            vec!["2".to_string(), "3".to_string()],
+            vec!["1".to_string()]
+        ]
+    );
+}
+
+#[test]
+fn boxed_iterator_try() {
+    #[derive(Functor)]
+    #[functor(lazy)]
+    struct Pending<T> {
+        items: Box<dyn ExactSizeIterator<Item = T>>,
+    }
+
+    let x = Pending {
+        items: Box::new(vec!["1", "2"].into_iter()),
+    };
+    let y = x.try_fmap(|x| x.parse::<u64>()).unwrap();
+    assert_eq!(y.items.len(), 2);
+    assert_eq!(y.items.collect::<Vec<_>>(), vec![1, 2]);
+
+    let x = Pending {
+        items: Box::new(vec!["1", "two"].into_iter()),
+    };
+    assert!(x.try_fmap(|x| x.parse::<u64>()).is_err());
+}
+
+#[test]
+fn nested_lazy() {
+    #[derive(Functor)]
+    #[functor(lazy)]
+    struct Pending<T> {
+        items: Box<dyn Iterator<Item = T>>,
+    }
+
+    #[derive(Functor)]
+    #[functor(lazy)]
+    struct Batches<T> {
+        batches: Pending<Vec<T>>,
+        first: Option<Pending<T>>,
+    }
+
+    let x = Batches {
+        batches: Pending {
+            items: Box::new(vec![vec![1, 2], vec![3]].into_iter()),
+        },
+        first: Some(Pending {
+            items: Box::new(0..2),
+        }),
+    };
+
+    let y = x.fmap(|x| x * 10);
+    assert_eq!(
+        y.batches.items.collect::<Vec<_>>(),
+        vec![vec![10, 20], vec![30]]
+    );
+    assert_eq!(y.first.unwrap().items.collect::<Vec<_>>(), vec![0, 10]);
+
+    let x = Batches {
+        batches: Pending {
+            items: Box::new(vec![vec![1, 2]].into_iter()),
+        },
+        first: None,
+    };
+    let y = x.try_fmap(|x| u8::try_from(x * 100)).unwrap();
+    assert_eq!(y.batches.items.collect::<Vec<_>>(), vec![vec![100, 200]]);
+}
+
+#[test]
+fn boxed_closure() {
+    #[derive(Functor)]
+    #[functor(lazy)]
+    struct Deferred<T> {
+        value: Box<dyn FnOnce() -> (T, usize) + Send>,
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let x = Deferred {
+        value: Box::new(|| ("2", 1)),
+    };
+
+    let counter = calls.clone();
+    let y = x.fmap(move |x: &str| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        x.len()
+    });
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+    assert_eq!((y.value)(), (1, 1));
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    let x = Deferred {
+        value: Box::new(|| ("two", 1)),
+    };
+    assert!(x.try_fmap(|x| x.parse::<u64>()).is_err());
+
+    let x = Deferred {
+        value: Box::new(|| ("2", 1)),
+    };
+    assert_eq!((x.try_fmap(|x| x.parse::<u64>()).unwrap().value)(), (2, 1));
+}
@@ -0,0 +1,99 @@
+use functor_derive::Functor;
+use std::marker::PhantomData;
+
+#[derive(Functor, Debug, PartialEq)]
+#[functor(once)]
+struct Spanned<T> {
+    value: T,
+    span: (usize, usize),
+}
+
+#[test]
+fn once_struct() {
+    // The closure moves its captured `String` out, so it is only `FnOnce`.
+    let suffix = String::from("!");
+    let x = Spanned {
+        value: String::from("hello"),
+        span: (0, 5),
+    };
+    assert_eq!(
+        x.fmap_once(move |value| value + &suffix),
+        Spanned {
+            value: String::from("hello!"),
+            span: (0, 5),
+        }
+    );
+}
+
+#[test]
+fn once_enum() {
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(once)]
+    enum Node<T> {
+        Leaf(T),
+        Tagged { tag: &'static str, inner: (u8, T) },
+        Marked(T, PhantomData<T>),
+    }
+
+    let owned = vec![1, 2, 3];
+    let take = |_: ()| owned;
+    assert_eq!(Node::Leaf(()).fmap_once(take), Node::Leaf(vec![1, 2, 3]));
+
+    let owned = vec![4];
+    let x = Node::Tagged {
+        tag: "t",
+        inner: (1, ()),
+    };
+    assert_eq!(
+        x.fmap_once(|_| owned),
+        Node::Tagged {
+            tag: "t",
+            inner: (1, vec![4]),
+        }
+    );
+}
+
+#[test]
+fn once_named() {
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(T as values, S as spans, once)]
+    struct Tagged<T, S> {
+        value: T,
+        span: S,
+    }
+
+    let label = String::from("label");
+    let x = Tagged { value: 1, span: 2 };
+    assert_eq!(
+        x.fmap_once_spans(move |_| label),
+        Tagged {
+            value: 1,
+            span: String::from("label"),
+        }
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn once_per_param() {
+    // `T` occurs in a `Vec`, so only `S` can get `fmap_once`.
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(T as values, S as spans, once = S)]
+    struct Tagged<T, S> {
+        values: Vec<T>,
+        span: S,
+    }
+
+    let label = String::from("label");
+    let x = Tagged {
+        values: vec![1, 2],
+        span: 3,
+    };
+    assert_eq!(
+        x.fmap_once_spans(move |_| label),
+        Tagged {
+            values: vec![1, 2],
+            span: String::from("label"),
+        }
+    );
+}
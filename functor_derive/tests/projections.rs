@@ -0,0 +1,97 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::Functor;
+use std::any::{Any, TypeId};
+
+trait Lang {
+    type Id;
+    type Annotation;
+}
+
+struct Parsed;
+
+struct Resolved;
+
+impl Lang for Parsed {
+    type Id = String;
+    type Annotation = ();
+}
+
+impl Lang for Resolved {
+    type Id = usize;
+    type Annotation = &'static str;
+}
+
+fn map_id<A: Lang, B: Lang>(_id: A::Id) -> B::Id
+where
+    B::Id: Default,
+{
+    B::Id::default()
+}
+
+fn map_annotation<A: Lang, B: Lang>(_annotation: A::Annotation) -> B::Annotation
+where
+    B::Annotation: Default,
+{
+    B::Annotation::default()
+}
+
+#[test]
+fn projection() {
+    #[derive(Functor)]
+    #[functor(L::Id => B::Id via map_id)]
+    struct Ir<L: Lang>
+    where
+        L::Id: Default,
+    {
+        ids: Vec<L::Id>,
+        lang: L,
+    }
+
+    let x = Ir::<Parsed> {
+        ids: vec!["a".to_string(), "b".to_string()],
+        lang: Parsed,
+    };
+
+    let y = x.fmap(|_| Resolved);
+    assert_eq!(y.ids, vec![0, 0]);
+    assert_eq!(y.type_id(), TypeId::of::<Ir<Resolved>>());
+}
+
+#[test]
+fn qualified_projection() {
+    #[derive(Functor)]
+    #[functor(L, <L as Lang>::Annotation => <B as Lang>::Annotation via map_annotation)]
+    enum Node<L: Lang>
+    where
+        L::Annotation: Default,
+    {
+        Leaf(<L as Lang>::Annotation),
+        Pair(Option<<L as Lang>::Annotation>, L),
+    }
+
+    let x = Node::<Parsed>::Pair(Some(()), Parsed);
+
+    let y = x.try_fmap(|_| Ok::<_, ()>(Resolved)).unwrap();
+    assert!(matches!(y, Node::Pair(Some(""), Resolved)));
+}
+
+#[test]
+fn projection_of_other_param() {
+    #[derive(Functor)]
+    #[functor(T)]
+    struct Tagged<L: Lang, T> {
+        id: L::Id,
+        value: T,
+    }
+
+    let x = Tagged::<Parsed, usize> {
+        id: "a".to_string(),
+        value: 42,
+    };
+
+    assert_eq!(
+        x.fmap(|x| x as u64).type_id(),
+        TypeId::of::<Tagged<Parsed, u64>>()
+    );
+}
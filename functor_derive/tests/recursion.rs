@@ -1,3 +1,4 @@
+#![cfg(feature = "alloc")]
 #![allow(unused_parens)]
 
 use functor_derive::Functor;
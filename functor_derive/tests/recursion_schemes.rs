@@ -0,0 +1,163 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::BaseFunctor;
+
+#[derive(BaseFunctor, Clone, Debug, PartialEq)]
+enum Expr {
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Neg(Box<Self>),
+    Lit(i64),
+}
+
+fn lit(x: i64) -> Box<Expr> {
+    Box::new(Expr::Lit(x))
+}
+
+#[test]
+fn project_embed() {
+    let x = Expr::Add(lit(1), lit(2));
+    let layer = x.clone().project();
+    assert!(matches!(layer, ExprF::Add(Expr::Lit(1), Expr::Lit(2))));
+    assert_eq!(Expr::embed(layer), x);
+}
+
+#[test]
+fn cata() {
+    let x = Expr::Mul(
+        Box::new(Expr::Add(lit(1), lit(2))),
+        Box::new(Expr::Neg(lit(4))),
+    );
+
+    assert_eq!(eval(x.clone()), -12);
+
+    let depth = x.cata(|layer: ExprF<usize>| match layer {
+        ExprF::Add(a, b) | ExprF::Mul(a, b) => 1 + a.max(b),
+        ExprF::Neg(a) => 1 + a,
+        ExprF::Lit(_) => 1,
+    });
+    assert_eq!(depth, 3);
+}
+
+fn eval(x: Expr) -> i64 {
+    x.cata(|layer: ExprF<i64>| match layer {
+        ExprF::Add(a, b) => a + b,
+        ExprF::Mul(a, b) => a * b,
+        ExprF::Neg(a) => -a,
+        ExprF::Lit(x) => x,
+    })
+}
+
+#[test]
+fn ana() {
+    // Builds a complete tree of additions of the given depth.
+    let tree = |depth| {
+        Expr::ana(depth, |n: u32| {
+            if n == 0 {
+                ExprF::Lit(1)
+            } else {
+                ExprF::Add(n - 1, n - 1)
+            }
+        })
+    };
+
+    assert_eq!(tree(1), Expr::Add(lit(1), lit(1)));
+    assert_eq!(eval(tree(5)), 32);
+}
+
+#[test]
+fn para() {
+    // Counts the additions whose left operand is a literal.
+    let x = Expr::Add(
+        lit(1),
+        Box::new(Expr::Add(Box::new(Expr::Neg(lit(2))), lit(3))),
+    );
+    let count = x.para(|layer: ExprF<(Expr, usize)>| match layer {
+        ExprF::Add((left, a), (_, b)) => a + b + usize::from(matches!(left, Expr::Lit(_))),
+        ExprF::Mul((_, a), (_, b)) => a + b,
+        ExprF::Neg((_, a)) => a,
+        ExprF::Lit(_) => 0,
+    });
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn rose_tree() {
+    #[derive(BaseFunctor, Debug, PartialEq)]
+    struct Rose<T> {
+        label: T,
+        children: Vec<Rose<T>>,
+        next: Option<Box<Rose<T>>>,
+    }
+
+    let x = Rose {
+        label: 1,
+        children: vec![
+            Rose {
+                label: 2,
+                children: vec![],
+                next: None,
+            },
+            Rose {
+                label: 3,
+                children: vec![],
+                next: Some(Box::new(Rose {
+                    label: 4,
+                    children: vec![],
+                    next: None,
+                })),
+            },
+        ],
+        next: None,
+    };
+
+    let sum = x.cata(|layer: RoseF<i32, i32>| {
+        layer.label + layer.children.iter().sum::<i32>() + layer.next.unwrap_or(0)
+    });
+    assert_eq!(sum, 10);
+
+    let x = Rose::ana(3, |n: u32| RoseF {
+        label: n,
+        children: (0..n).collect(),
+        next: None,
+    });
+    assert_eq!(
+        x.cata(|layer: RoseF<u32, usize>| 1 + layer.children.iter().sum::<usize>()),
+        8
+    );
+}
+
+#[test]
+fn other_instantiation_is_not_recursive() {
+    // `Tagged<u8>` is a different type than `Tagged<T>`, so it stays a field of the base functor.
+    #[derive(BaseFunctor, Debug, PartialEq)]
+    struct Tagged<T> {
+        label: T,
+        tag: Option<Box<Tagged<u8>>>,
+        children: Vec<Tagged<T>>,
+    }
+
+    let tag = Tagged {
+        label: 7u8,
+        tag: None,
+        children: vec![],
+    };
+    let x = Tagged {
+        label: "root",
+        tag: Some(Box::new(tag)),
+        children: vec![Tagged {
+            label: "child",
+            tag: None,
+            children: vec![],
+        }],
+    };
+
+    let layer: TaggedF<&str, Tagged<&str>> = x.project();
+    assert_eq!(layer.tag.as_ref().map(|tag| tag.label), Some(7));
+    assert_eq!(layer.children.len(), 1);
+    assert_eq!(
+        Tagged::embed(layer)
+            .cata(|layer: TaggedF<&str, usize>| 1 + layer.children.iter().sum::<usize>()),
+        2
+    );
+}
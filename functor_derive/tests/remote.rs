@@ -0,0 +1,107 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::Functor;
+use std::any::{Any, TypeId};
+
+/// Stands in for a crate we do not own.
+mod other {
+    pub struct Spanned<T> {
+        pub value: T,
+        pub span: (usize, usize),
+    }
+
+    pub enum Either<L, R> {
+        Left(L),
+        Right(R),
+    }
+}
+
+use other::{Either, Spanned};
+
+#[allow(dead_code)]
+#[derive(Functor)]
+#[functor(remote = other::Spanned)]
+struct SpannedDef<T> {
+    value: T,
+    span: (usize, usize),
+}
+
+#[allow(dead_code)]
+#[derive(Functor)]
+#[functor(L as left, R as right, remote = other::Either)]
+enum EitherDef<L, R> {
+    Left(L),
+    Right(R),
+}
+
+fn spanned<T>(value: T) -> Spanned<T> {
+    Spanned {
+        value,
+        span: (3, 5),
+    }
+}
+
+#[test]
+fn remote_struct() {
+    let x = SpannedDef::fmap(spanned(42usize), |x| x as u64);
+
+    assert_eq!(x.value, 42);
+    assert_eq!(x.span, (3, 5));
+    assert_eq!(x.type_id(), TypeId::of::<Spanned<u64>>());
+}
+
+#[test]
+fn remote_enum() {
+    let x = EitherDef::fmap_left(Either::<usize, bool>::Left(42), |x| x as u64);
+    assert!(matches!(x, Either::<u64, bool>::Left(42)));
+
+    let x = EitherDef::fmap_right(Either::<bool, usize>::Right(42), |x| x as u64);
+    assert!(matches!(x, Either::<bool, u64>::Right(42)));
+}
+
+#[test]
+fn remote_fields() {
+    #[derive(Functor)]
+    #[functor(Spanned via SpannedDef, other::Either via EitherDef)]
+    struct Ast<T> {
+        nodes: Vec<Spanned<T>>,
+        root: Option<Spanned<other::Either<T, usize>>>,
+        choice: other::Either<usize, T>,
+    }
+
+    let x = Ast {
+        nodes: vec![spanned(1usize), spanned(2)],
+        root: Some(spanned(other::Either::Left(3))),
+        choice: Either::Right(4),
+    };
+
+    let y = x.fmap(|x| x as u64 * 10);
+
+    assert_eq!(
+        y.nodes.iter().map(|n| n.value).collect::<Vec<_>>(),
+        [10, 20]
+    );
+    assert_eq!(y.nodes[0].span, (3, 5));
+    assert!(matches!(y.root.unwrap().value, Either::Left(30)));
+    assert!(matches!(y.choice, Either::Right(40)));
+}
+
+#[test]
+fn remote_fields_try() {
+    #[derive(Functor)]
+    #[functor(Spanned via SpannedDef)]
+    struct Tokens<T> {
+        tokens: Vec<Spanned<T>>,
+    }
+
+    let x = Tokens {
+        tokens: vec![spanned("1"), spanned("2")],
+    };
+    let y = x.try_fmap(|x| x.parse::<u64>()).unwrap();
+    assert_eq!(y.tokens[1].value, 2);
+
+    let x = Tokens {
+        tokens: vec![spanned("1"), spanned("two")],
+    };
+    assert!(x.try_fmap(|x| x.parse::<u64>()).is_err());
+}
@@ -1,8 +1,10 @@
+#![cfg(feature = "std")]
 #![allow(unused_parens)]
 
 use functor_derive::Functor;
 use std::any::{Any, TypeId};
 use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
 
 // recursion,
 // mutual recursion,
@@ -66,6 +68,25 @@ fn struct_tuple() {
     );
 }
 
+#[test]
+fn struct_large_tuple() {
+    #[allow(clippy::type_complexity)]
+    #[derive(Functor)]
+    struct StructLargeTuple<A> {
+        field_1: (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, A),
+        field_2: Vec<(u8, A)>,
+    }
+
+    let x = StructLargeTuple::<usize> {
+        field_1: (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12),
+        field_2: vec![(13, 14)],
+    };
+
+    let y = x.fmap(|x| x as u64);
+    assert_eq!(y.field_1.12, 12u64);
+    assert_eq!(y.field_2, vec![(13, 14u64)]);
+}
+
 #[test]
 fn struct_hashmap() {
     #[derive(Functor)]
@@ -237,6 +258,7 @@ fn indirect_generic() {
 
 #[test]
 fn indirect_tuple_generic() {
+    #[allow(clippy::type_complexity)]
     #[derive(Functor)]
     struct IndirectTupleGeneric<A> {
         field_1: Vec<Vec<(usize, Vec<A>, Vec<Vec<A>>, usize)>>,
@@ -282,3 +304,76 @@ fn explicit_path() {
     #[derive(Functor)]
     struct Test<T>(core::option::Option<T>);
 }
+
+#[test]
+fn macro_type() {
+    macro_rules! wrapped {
+        ($t:ty) => {
+            #[derive(Functor)]
+            struct Wrapped<A> {
+                field_1: Option<$t>,
+            }
+        };
+    }
+
+    wrapped!(Vec<A>);
+
+    let x = Wrapped {
+        field_1: Some(vec![42usize]),
+    };
+
+    assert_eq!(x.fmap(|x| x as u64).type_id(), TypeId::of::<Wrapped<u64>>());
+}
+
+#[test]
+fn fn_sugar_of_other_param() {
+    #[derive(Functor)]
+    #[functor(A)]
+    struct Callback<A, R> {
+        field_1: A,
+        field_2: Box<dyn FnOnce() -> R>,
+    }
+
+    let x = Callback::<usize, bool> {
+        field_1: 42,
+        field_2: Box::new(|| true),
+    };
+
+    let y = x.fmap(|x| x as u64);
+    assert_eq!(y.type_id(), TypeId::of::<Callback<u64, bool>>());
+    assert!((y.field_2)());
+}
+
+#[test]
+fn variance_markers() {
+    #[allow(clippy::type_complexity)]
+    #[derive(Functor)]
+    struct Handle<'a, T> {
+        id: usize,
+        invariant: PhantomData<fn(T) -> T>,
+        contravariant: PhantomData<fn(T)>,
+        pointer: PhantomData<*const T>,
+        borrowed: PhantomData<&'a std::collections::HashSet<T>>,
+    }
+
+    #[derive(Functor)]
+    enum Slot<T> {
+        Empty(PhantomData<fn() -> T>),
+        Full(T),
+    }
+
+    let x = Handle::<usize> {
+        id: 42,
+        invariant: PhantomData,
+        contravariant: PhantomData,
+        pointer: PhantomData,
+        borrowed: PhantomData,
+    };
+
+    let y = x.fmap(|x| x as u64);
+    assert_eq!(y.id, 42);
+    assert_eq!(y.type_id(), TypeId::of::<Handle<u64>>());
+
+    let x = Slot::<usize>::Empty(PhantomData);
+    assert!(matches!(x.fmap(|x| x as u64), Slot::<u64>::Empty(_)));
+}
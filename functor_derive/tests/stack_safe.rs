@@ -0,0 +1,145 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::Functor;
+
+const DEPTH: usize = 1_000_000;
+
+#[derive(Functor, Debug, PartialEq)]
+#[functor(stack_safe)]
+struct List<T> {
+    value: T,
+    next: Option<Box<List<T>>>,
+}
+
+impl<T> List<T> {
+    fn from_range(n: usize, f: impl Fn(usize) -> T) -> Self {
+        let mut list = List {
+            value: f(n - 1),
+            next: None,
+        };
+        for i in (0..n - 1).rev() {
+            list = List {
+                value: f(i),
+                next: Some(Box::new(list)),
+            };
+        }
+        list
+    }
+
+    /// Takes the list apart node by node, as dropping a deep list recurses as well.
+    fn into_vec(self) -> Vec<T> {
+        let mut values = Vec::new();
+        let mut next = Some(Box::new(self));
+        while let Some(node) = next {
+            values.push(node.value);
+            next = node.next;
+        }
+        values
+    }
+}
+
+#[test]
+fn deep_list() {
+    let x = List::from_range(DEPTH, |i| i);
+    let values = x.fmap(|i| i as u64 * 2).into_vec();
+
+    assert_eq!(values.len(), DEPTH);
+    assert!(values.iter().enumerate().all(|(i, &v)| v == i as u64 * 2));
+}
+
+#[test]
+fn deep_list_try() {
+    let x = List::from_range(DEPTH, |i| i);
+    assert_eq!(
+        x.try_fmap(|i| if i == DEPTH - 1 { Err(i) } else { Ok(i) }),
+        Err(DEPTH - 1)
+    );
+
+    let x = List::from_range(DEPTH, |i| i.to_string());
+    let values = x.try_fmap(|s| s.parse::<usize>()).unwrap().into_vec();
+    assert_eq!(values[DEPTH - 1], DEPTH - 1);
+}
+
+#[test]
+fn tree_order() {
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(stack_safe)]
+    enum Tree<T> {
+        Leaf(T),
+        Node(Box<Tree<T>>, Vec<Self>),
+    }
+
+    let x = Tree::Node(
+        Box::new(Tree::Leaf(1)),
+        vec![Tree::Leaf(2), Tree::Node(Box::new(Tree::Leaf(3)), vec![])],
+    );
+
+    // Values are visited depth-first from left to right.
+    let visited = std::cell::RefCell::new(Vec::new());
+    let y = x.fmap(|v| {
+        visited.borrow_mut().push(v);
+        v.to_string()
+    });
+    assert_eq!(visited.into_inner(), vec![1, 2, 3]);
+    assert_eq!(
+        y,
+        Tree::Node(
+            Box::new(Tree::Leaf("1".to_string())),
+            vec![
+                Tree::Leaf("2".to_string()),
+                Tree::Node(Box::new(Tree::Leaf("3".to_string())), vec![]),
+            ],
+        )
+    );
+}
+
+#[test]
+fn named_params() {
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(K as keys, V as values, stack_safe)]
+    enum Assoc<K, V> {
+        Cons(K, V, Box<Assoc<K, V>>),
+        Nil,
+    }
+
+    let x = Assoc::Cons(1, "a", Box::new(Assoc::Cons(2, "b", Box::new(Assoc::Nil))));
+    assert_eq!(
+        x.fmap_keys(|k| k * 10).fmap_values(str::len),
+        Assoc::Cons(10, 1, Box::new(Assoc::Cons(20, 1, Box::new(Assoc::Nil))))
+    );
+}
+
+#[test]
+fn unmappable_param() {
+    // `U` is behind a reference and cannot be mapped, which must not break mapping `T` stack-safely.
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(stack_safe)]
+    struct Labelled<'a, T, U> {
+        value: T,
+        label: &'a U,
+        next: Option<Box<Labelled<'a, T, U>>>,
+    }
+
+    let label = "x";
+    let x = Labelled {
+        value: 1,
+        label: &label,
+        next: Some(Box::new(Labelled {
+            value: 2,
+            label: &label,
+            next: None,
+        })),
+    };
+    assert_eq!(
+        x.fmap(|x| x * 10),
+        Labelled {
+            value: 10,
+            label: &label,
+            next: Some(Box::new(Labelled {
+                value: 20,
+                label: &label,
+                next: None,
+            })),
+        }
+    );
+}
@@ -1,6 +1,23 @@
-use functor_derive::impl_std::{FunctorHashKeys, FunctorHashSet};
-use functor_derive::{Functor, FunctorValues};
-use std::collections::{HashMap, HashSet};
+#![cfg(feature = "std")]
+
+use functor_derive::impl_std::{FunctorHashEntries, FunctorHashKeys, FunctorHashSet};
+use functor_derive::{
+    Functor, Functor0, Functor1, Functor11, Functor5, FunctorCow, FunctorOrd, FunctorOrdEntries,
+    FunctorOrdKeys, FunctorValues, KeyCollision,
+};
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+use std::mem::ManuallyDrop;
+use std::num::{Saturating, Wrapping};
+use std::ops::Bound;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::task::Poll;
 
 fn map(value: usize) -> u64 {
     value as u64
@@ -51,3 +68,663 @@ fn hashset() {
 
     assert_eq!(x.fmap(map), HashSet::from([42u64, 13u64]));
 }
+
+#[test]
+fn range() {
+    assert_eq!((1usize..3).fmap(map), 1u64..3);
+    assert_eq!((1usize..=3).fmap(map), 1u64..=3);
+    assert_eq!((1usize..).fmap(map), 1u64..);
+    assert_eq!((..3usize).fmap(map), ..3u64);
+    assert_eq!((..=3usize).fmap(map), ..=3u64);
+}
+
+#[test]
+fn range_try() {
+    assert_eq!(("1".."3").try_fmap(str::parse::<u64>), Ok(1..3));
+    assert!(("1"..="three").try_fmap(str::parse::<u64>).is_err());
+}
+
+#[test]
+fn bound() {
+    assert_eq!(Bound::Excluded(42usize).fmap(map), Bound::Excluded(42u64));
+    assert_eq!(Bound::<usize>::Unbounded.fmap(map), Bound::<u64>::Unbounded);
+}
+
+#[test]
+fn poll() {
+    assert_eq!(Poll::Ready(42usize).fmap(map), Poll::Ready(42u64));
+    assert_eq!(Poll::<usize>::Pending.fmap(map), Poll::<u64>::Pending);
+}
+
+#[test]
+fn vec_into_iter() {
+    let mut x = vec![1usize, 2, 3].into_iter();
+    x.next();
+
+    assert_eq!(x.fmap(map).collect::<Vec<_>>(), vec![2u64, 3u64]);
+}
+
+#[test]
+fn hashmap_into_iter_values() {
+    let x = HashMap::from([(42usize, 13usize)]).into_iter();
+
+    assert_eq!(
+        x.fmap_values(map).collect::<Vec<_>>(),
+        vec![(42usize, 13u64)]
+    );
+}
+
+#[test]
+fn into_iter_key_collision() {
+    // The iterators keep the entry that comes last, like the collections they iterate over.
+    let x = BTreeMap::from([(1usize, 'a'), (2, 'b')]);
+    assert_eq!(
+        x.clone()
+            .into_iter()
+            .fmap_keys(|_| 0usize)
+            .collect::<Vec<_>>(),
+        x.fmap_keys(|_| 0usize).into_iter().collect::<Vec<_>>()
+    );
+
+    let x = HashMap::from([(1usize, 'a'), (2, 'b')]);
+    assert_eq!(
+        x.clone()
+            .into_iter()
+            .fmap_keys(|_| 0usize)
+            .collect::<Vec<_>>(),
+        x.fmap_keys(|_| 0usize).into_iter().collect::<Vec<_>>()
+    );
+
+    let x = BTreeSet::from([1usize, 2]);
+    assert_eq!(
+        x.clone().into_iter().fmap(|_| 0usize).collect::<Vec<_>>(),
+        x.fmap(|_| 0usize).into_iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn hashmap_into_iter_keys_with() {
+    let x = HashMap::from([(1usize, 1usize), (2, 2)]).into_iter();
+
+    assert_eq!(
+        x.fmap_keys_with(|_| 0usize, |a, b| a + b)
+            .collect::<Vec<_>>(),
+        vec![(0, 3)]
+    );
+}
+
+#[test]
+fn rc_unique() {
+    let x = Rc::new(42usize);
+
+    assert_eq!(x.fmap(map), Rc::new(42u64));
+}
+
+#[test]
+fn rc_shared() {
+    let x = Rc::new(42usize);
+    let y = x.clone();
+
+    assert_eq!(x.fmap(map), Rc::new(42u64));
+    assert_eq!(y, Rc::new(42usize));
+}
+
+#[test]
+fn rc_slice() {
+    let x: Rc<[usize]> = Rc::from([42usize, 13usize]);
+
+    assert_eq!(x.fmap(map), Rc::from([42u64, 13u64]));
+}
+
+#[test]
+fn arc_shared() {
+    let x = Arc::new(42usize);
+    let y = x.clone();
+
+    assert_eq!(x.try_fmap(|x| Ok::<_, ()>(map(x))), Ok(Arc::new(42u64)));
+    assert_eq!(y, Arc::new(42usize));
+}
+
+#[test]
+fn arc_slice() {
+    let x: Arc<[usize]> = Arc::from([42usize, 13usize]);
+
+    assert_eq!(x.fmap(map), Arc::from([42u64, 13u64]));
+}
+
+#[test]
+fn cow() {
+    let value = 42usize;
+    let x = Cow::Borrowed(&value);
+    let y = Cow::<usize>::Owned(13usize);
+
+    assert_eq!(x.fmap(map), Cow::<u64>::Owned(42u64));
+    assert_eq!(y.fmap(map), Cow::<u64>::Owned(13u64));
+}
+
+#[test]
+fn cow_slice() {
+    let values = [42usize, 13usize];
+    let x = Cow::Borrowed(&values[..]);
+
+    assert_eq!(x.fmap(map), Cow::<[u64]>::Owned(vec![42u64, 13u64]));
+}
+
+#[test]
+fn shared_tree() {
+    #[derive(Functor, Debug, PartialEq)]
+    enum Tree<'a, T: Clone> {
+        Leaf(Cow<'a, T>),
+        Node(Rc<Tree<'a, T>>, Arc<T>),
+    }
+
+    impl<T: Clone> Clone for Tree<'_, T> {
+        fn clone(&self) -> Self {
+            match self {
+                Tree::Leaf(x) => Tree::Leaf(x.clone()),
+                Tree::Node(x, y) => Tree::Node(x.clone(), y.clone()),
+            }
+        }
+    }
+
+    let leaf = 1usize;
+    let shared = Rc::new(Tree::Leaf(Cow::Borrowed(&leaf)));
+    let x = Tree::Node(shared.clone(), Arc::new(2usize));
+
+    assert_eq!(
+        x.fmap(map),
+        Tree::Node(Rc::new(Tree::Leaf(Cow::Owned(1u64))), Arc::new(2u64))
+    );
+    assert_eq!(*shared, Tree::Leaf(Cow::Borrowed(&1usize)));
+}
+
+#[test]
+fn mutex() {
+    let x = Mutex::new(42usize);
+
+    assert_eq!(x.fmap(map).into_inner().unwrap(), 42u64);
+}
+
+#[test]
+fn mutex_poisoned() {
+    let x = Mutex::new(42usize);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = x.lock().unwrap();
+        panic!();
+    });
+    assert!(x.is_poisoned());
+
+    let y = x.fmap(map);
+    assert!(!y.is_poisoned());
+    assert_eq!(y.into_inner().unwrap(), 42u64);
+}
+
+#[test]
+fn rwlock() {
+    let x = RwLock::new(42usize);
+
+    assert_eq!(
+        x.try_fmap(|x| Ok::<_, ()>(map(x)))
+            .unwrap()
+            .into_inner()
+            .unwrap(),
+        42u64
+    );
+}
+
+#[test]
+fn once_lock() {
+    let x = OnceLock::from(42usize);
+    let y = OnceLock::<usize>::new();
+
+    assert_eq!(x.fmap(map).into_inner(), Some(42u64));
+    assert_eq!(y.fmap(|_| -> u64 { unreachable!() }).into_inner(), None);
+}
+
+#[test]
+fn once_cell() {
+    let x = OnceCell::from(42usize);
+    let y = OnceCell::<usize>::new();
+
+    assert_eq!(x.fmap(map).into_inner(), Some(42u64));
+    assert_eq!(y.fmap(|_| -> u64 { unreachable!() }).into_inner(), None);
+}
+
+#[test]
+fn state() {
+    #[derive(Functor)]
+    struct State<T> {
+        current: Mutex<T>,
+        history: RwLock<Vec<T>>,
+        initial: OnceLock<T>,
+    }
+
+    let x = State {
+        current: Mutex::new(42usize),
+        history: RwLock::new(vec![13usize]),
+        initial: OnceLock::from(1usize),
+    };
+
+    let y = x.fmap(map);
+    assert_eq!(y.current.into_inner().unwrap(), 42u64);
+    assert_eq!(y.history.into_inner().unwrap(), vec![13u64]);
+    assert_eq!(y.initial.into_inner(), Some(1u64));
+}
+
+#[test]
+fn wrappers() {
+    assert_eq!(Reverse(42usize).fmap(map), Reverse(42u64));
+    assert_eq!(Wrapping(42usize).fmap(map), Wrapping(42u64));
+    assert_eq!(Saturating(42usize).fmap(map), Saturating(42u64));
+    assert_eq!(
+        ManuallyDrop::new(42usize).fmap(map),
+        ManuallyDrop::new(42u64)
+    );
+}
+
+#[test]
+fn pin_box() {
+    let x = Box::pin(42usize);
+
+    assert_eq!(x.fmap(map), Box::pin(42u64));
+}
+
+#[test]
+fn pin_box_derived() {
+    #[derive(Functor)]
+    struct Pinned<T: Unpin> {
+        value: Pin<Box<T>>,
+        pair: Option<Pin<Box<(T, usize)>>>,
+    }
+
+    let x = Pinned {
+        value: Box::pin(42usize),
+        pair: Some(Box::pin((13usize, 1))),
+    };
+
+    let y = x.try_fmap(|x| Ok::<_, ()>(map(x))).unwrap();
+    assert_eq!(*y.value, 42u64);
+    assert_eq!(*y.pair.unwrap(), (13u64, 1));
+}
+
+#[test]
+fn binary_heap_reverse() {
+    let x = BinaryHeap::from([Reverse(42usize), Reverse(13usize)]);
+
+    assert_eq!(
+        x.fmap(|x| x.fmap(map)).into_sorted_vec(),
+        vec![Reverse(42u64), Reverse(13u64)]
+    );
+}
+
+#[test]
+fn binary_heap_reverse_derived() {
+    #[derive(Functor)]
+    struct Queue<T: Ord> {
+        pending: BinaryHeap<Reverse<T>>,
+    }
+
+    let x = Queue {
+        pending: BinaryHeap::from([Reverse(42usize), Reverse(13usize), Reverse(20usize)]),
+    };
+
+    let mut y = x.fmap(|x| 100 - x as i64);
+    assert_eq!(y.pending.pop(), Some(Reverse(58i64)));
+    assert_eq!(y.pending.pop(), Some(Reverse(80i64)));
+    assert_eq!(y.pending.pop(), Some(Reverse(87i64)));
+}
+
+#[test]
+fn tuple() {
+    let x = (1u8, 42usize);
+
+    assert_eq!(x.fmap(map), (1u8, 42u64));
+    assert_eq!(x.fmap_1(map), (1u8, 42u64));
+    assert_eq!(x.fmap_0(|x| x as u16), (1u16, 42usize));
+    assert_eq!((42usize,).fmap(map), (42u64,));
+}
+
+#[test]
+fn tuple_try() {
+    let x = ("42", 13usize);
+
+    assert_eq!(x.try_fmap_0(|x| x.parse::<u64>()), Ok((42u64, 13usize)));
+    assert!(("x", 13usize).try_fmap_0(|x| x.parse::<u64>()).is_err());
+}
+
+#[test]
+fn tuple_twelve() {
+    let x = (
+        0u8, 1u8, 2u8, 3u8, 4u8, 5usize, 6u8, 7u8, 8u8, 9u8, 10u8, 11usize,
+    );
+
+    let y = x.fmap(map).fmap_5(map);
+    assert_eq!(y, (0, 1, 2, 3, 4, 5u64, 6, 7, 8, 9, 10, 11u64));
+    assert_eq!(y.fmap_11(|x| x + 1).11, 12u64);
+}
+
+#[test]
+fn tuple_generic() {
+    fn double<F: Functor<usize>>(x: F) -> F::Target<usize> {
+        x.fmap(|x| x * 2)
+    }
+
+    assert_eq!(double(("a", 21usize)), ("a", 42usize));
+    assert_eq!(double(vec![21usize]), vec![42usize]);
+}
+
+#[test]
+fn box_slice() {
+    let x: Box<[usize]> = Box::new([42usize, 13usize]);
+
+    assert_eq!(x.fmap(map), Box::from([42u64, 13u64]));
+}
+
+#[test]
+fn slice_fields() {
+    #[allow(clippy::type_complexity)]
+    #[derive(Functor)]
+    struct Slices<'a, T: Clone> {
+        boxed: Box<[T]>,
+        nested: Option<Box<[Vec<T>]>>,
+        rc: Rc<[T]>,
+        arc: Arc<[(T, usize)]>,
+        cow: Cow<'a, [T]>,
+    }
+
+    let borrowed = [5usize];
+    let x = Slices {
+        boxed: Box::new([1usize, 2]),
+        nested: Some(Box::new([vec![3usize]])),
+        rc: Rc::from([4usize]),
+        arc: Arc::from([(5usize, 6)]),
+        cow: Cow::Borrowed(&borrowed[..]),
+    };
+
+    let y = x.try_fmap(|x| Ok::<_, ()>(map(x))).unwrap();
+    assert_eq!(*y.boxed, [1u64, 2]);
+    assert_eq!(*y.nested.unwrap(), [vec![3u64]]);
+    assert_eq!(*y.rc, [4u64]);
+    assert_eq!(*y.arc, [(5u64, 6)]);
+    assert_eq!(*y.cow, [5u64]);
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct SeededState(u64);
+
+impl BuildHasher for SeededState {
+    type Hasher = DefaultHasher;
+
+    fn build_hasher(&self) -> DefaultHasher {
+        let mut hasher = DefaultHasher::new();
+        std::hash::Hasher::write_u64(&mut hasher, self.0);
+        hasher
+    }
+}
+
+type SeededMap<K, V> = HashMap<K, V, SeededState>;
+
+#[test]
+fn hashmap_custom_hasher() {
+    let mut x = SeededMap::with_hasher(SeededState(7));
+    x.insert(42usize, 13usize);
+
+    let y = x.clone().fmap_values(map);
+    assert_eq!(y.hasher(), &SeededState(7));
+    assert_eq!(y[&42], 13u64);
+
+    let y = x.fmap_keys(map);
+    assert_eq!(y.hasher(), &SeededState(7));
+    assert_eq!(y[&42u64], 13usize);
+}
+
+#[test]
+fn hashset_custom_hasher() {
+    let mut x = HashSet::with_hasher(SeededState(7));
+    x.insert(42usize);
+
+    let y = x.try_fmap(|x| Ok::<_, ()>(map(x))).unwrap();
+    assert_eq!(y.hasher(), &SeededState(7));
+    assert!(y.contains(&42u64));
+}
+
+#[test]
+fn custom_hasher_derived() {
+    #[derive(Functor)]
+    struct Index<T: Hash + Eq, S: BuildHasher + Clone> {
+        by_name: HashMap<String, T, S>,
+        seen: HashSet<T, S>,
+        aliases: SeededMap<T, String>,
+    }
+
+    let x: Index<usize, SeededState> = Index {
+        by_name: HashMap::from_iter([("a".to_string(), 42usize)]),
+        seen: HashSet::from_iter([13usize]),
+        aliases: SeededMap::from_iter([(1usize, "b".to_string())]),
+    };
+
+    let y = x.fmap(map);
+    assert_eq!(y.by_name["a"], 42u64);
+    assert!(y.seen.contains(&13u64));
+    assert_eq!(y.aliases[&1u64], "b");
+}
+
+#[test]
+fn btreemap_keys_with() {
+    let x = BTreeMap::from([(1usize, 1usize), (2, 2), (3, 3)]);
+
+    assert_eq!(
+        x.fmap_keys_with(|k| k / 2, |v1, v2| v1 * 10 + v2),
+        BTreeMap::from([(0usize, 1usize), (1, 23)])
+    );
+}
+
+#[test]
+fn btreemap_keys_strict() {
+    let x = BTreeMap::from([(1usize, 1usize), (2, 2), (3, 3)]);
+
+    assert_eq!(
+        x.clone().try_fmap_keys_strict(|k| k * 2),
+        Ok(BTreeMap::from([(2usize, 1usize), (4, 2), (6, 3)]))
+    );
+    assert_eq!(
+        x.try_fmap_keys_strict(|k| k / 2),
+        Err(KeyCollision { key: 1usize })
+    );
+}
+
+#[test]
+fn btreemap_entries() {
+    let x = BTreeMap::from([(1usize, "a"), (2, "b")]);
+
+    assert_eq!(
+        x.fmap_entries(|k, v| (v, map(k))),
+        BTreeMap::from([("a", 1u64), ("b", 2)])
+    );
+}
+
+#[test]
+fn btreeset_strict() {
+    let x = BTreeSet::from([1usize, 2, 3]);
+
+    assert_eq!(
+        x.clone().try_fmap_strict(map),
+        Ok(BTreeSet::from([1u64, 2, 3]))
+    );
+    assert_eq!(x.try_fmap_strict(|x| x / 2), Err(KeyCollision { key: 1 }));
+}
+
+#[test]
+fn hashmap_keys_with() {
+    let x = HashMap::from([(1usize, 1usize), (2, 2), (3, 3)]);
+
+    assert_eq!(
+        x.fmap_keys_with(|k| k / 2, |v1, v2| v1 + v2),
+        HashMap::from([(0usize, 1usize), (1, 5)])
+    );
+}
+
+#[test]
+fn hashmap_keys_strict() {
+    let x = HashMap::from([(1usize, 1usize), (2, 2), (3, 3)]);
+
+    assert!(x.clone().try_fmap_keys_strict(|k| k * 2).is_ok());
+    assert_eq!(
+        x.try_fmap_keys_strict(|k| k / 2),
+        Err(KeyCollision { key: 1usize })
+    );
+}
+
+#[test]
+fn hashmap_entries() {
+    let x = HashMap::from([(1usize, "a"), (2, "b")]);
+
+    assert_eq!(
+        x.try_fmap_entries(|k, v| Ok::<_, ()>((v, map(k)))),
+        Ok(HashMap::from([("a", 1u64), ("b", 2)]))
+    );
+}
+
+#[test]
+fn hashset_strict() {
+    let x = HashSet::from([1usize, 2, 3]);
+
+    assert!(x.clone().try_fmap_strict(map).is_ok());
+    assert_eq!(x.try_fmap_strict(|_| 0usize), Err(KeyCollision { key: 0 }));
+}
+
+#[derive(Functor)]
+#[functor(collisions = panic)]
+struct Buckets<T: Ord + Hash + Eq> {
+    by_key: BTreeMap<T, usize>,
+    seen: HashSet<T>,
+}
+
+#[test]
+fn collisions_panic() {
+    let x = Buckets {
+        by_key: BTreeMap::from([(1usize, 1), (2, 2)]),
+        seen: HashSet::from([1usize, 2]),
+    };
+
+    let y = x.try_fmap(|x| Ok::<_, ()>(map(x))).unwrap();
+    assert_eq!(y.by_key, BTreeMap::from([(1u64, 1), (2, 2)]));
+}
+
+#[test]
+#[should_panic(expected = "Two keys of a `BTreeMap` were mapped to the same key.")]
+fn collisions_panic_on_collision() {
+    let x = Buckets {
+        by_key: BTreeMap::from([(1usize, 1), (2, 2)]),
+        seen: HashSet::new(),
+    };
+
+    x.fmap(|_| 0u64);
+}
+
+#[derive(Functor, Debug)]
+#[functor(collisions = strict)]
+struct StrictBuckets<T: Ord + Hash + Eq> {
+    by_key: BTreeMap<T, Vec<T>>,
+    seen: Vec<HashSet<(T, u8)>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum BucketError {
+    Parse,
+    Key(String),
+    Element(String, u8),
+}
+
+impl From<KeyCollision<String>> for BucketError {
+    fn from(collision: KeyCollision<String>) -> Self {
+        BucketError::Key(collision.key)
+    }
+}
+
+impl From<KeyCollision<(String, u8)>> for BucketError {
+    fn from(collision: KeyCollision<(String, u8)>) -> Self {
+        BucketError::Element(collision.key.0, collision.key.1)
+    }
+}
+
+#[test]
+fn collisions_strict() {
+    let x = || StrictBuckets {
+        by_key: BTreeMap::from([(1usize, vec![2]), (11, vec![12])]),
+        seen: vec![
+            HashSet::from([(3usize, 0)]),
+            HashSet::from([(4, 0), (14, 0)]),
+        ],
+    };
+    let last_digit = |x: usize| Ok::<_, BucketError>((x % 10).to_string());
+
+    assert_eq!(
+        x().try_fmap(|x| Ok::<_, BucketError>(x.to_string()))
+            .unwrap()
+            .by_key["11"],
+        vec!["12".to_string()]
+    );
+    assert_eq!(
+        x().try_fmap(last_digit).unwrap_err(),
+        BucketError::Key("1".to_string())
+    );
+    assert_eq!(
+        x().try_fmap(|x| if x > 10 {
+            Err(BucketError::Parse)
+        } else {
+            Ok(x.to_string())
+        })
+        .unwrap_err(),
+        BucketError::Parse
+    );
+
+    let x = StrictBuckets {
+        by_key: BTreeMap::new(),
+        seen: vec![HashSet::from([(4usize, 0), (14, 0)])],
+    };
+    assert_eq!(
+        x.try_fmap(last_digit).unwrap_err(),
+        BucketError::Element("4".to_string(), 0)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Two keys of a `BTreeMap` were mapped to the same key.")]
+fn collisions_strict_fmap_panics() {
+    let x = StrictBuckets {
+        by_key: BTreeMap::from([(1usize, vec![]), (2, vec![])]),
+        seen: Vec::new(),
+    };
+
+    x.fmap(|_| 0u64);
+}
+
+#[test]
+fn collisions_merge() {
+    fn combine<V: Extend<V::Item> + IntoIterator>(mut first: V, second: V) -> V {
+        first.extend(second);
+        first
+    }
+
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(collisions = merge via combine)]
+    struct Index<T: Hash + Eq> {
+        postings: HashMap<T, Vec<usize>>,
+        terms: HashSet<T>,
+    }
+
+    let x = Index {
+        postings: HashMap::from([("Rust", vec![1]), ("rust", vec![2, 3])]),
+        terms: HashSet::from(["Rust", "rust"]),
+    };
+    let mut y = x.fmap(|term| term.to_lowercase());
+    y.postings.get_mut("rust").unwrap().sort();
+    assert_eq!(
+        y,
+        Index {
+            postings: HashMap::from([("rust".to_string(), vec![1, 2, 3])]),
+            terms: HashSet::from(["rust".to_string()]),
+        }
+    );
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "alloc")]
+
+use functor_derive::{Functor, FunctorJoin, FunctorTranspose};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+#[test]
+fn transpose_std() {
+    assert_eq!(vec![Some(1), Some(2)].transpose(), Some(vec![1, 2]));
+    assert_eq!(vec![Some(1), None].transpose(), None);
+    assert_eq!([Ok::<_, &str>(1), Ok(2)].transpose(), Ok([1, 2]));
+    assert_eq!(
+        BTreeMap::from([(1, Ok(1)), (2, Err("two")), (3, Err("three"))]).transpose(),
+        Err("two")
+    );
+    assert_eq!(("a", Some(1)).transpose(), Some(("a", 1)));
+}
+
+#[test]
+fn transpose_derived() {
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(transpose)]
+    struct Form<T> {
+        name: T,
+        fields: Vec<T>,
+        unaffected: bool,
+    }
+
+    let x = Form {
+        name: Some("form"),
+        fields: vec![Some("a"), Some("b")],
+        unaffected: true,
+    };
+    assert_eq!(
+        x.transpose(),
+        Some(Form {
+            name: "form",
+            fields: vec!["a", "b"],
+            unaffected: true,
+        })
+    );
+
+    let x = Form {
+        name: Ok("form"),
+        fields: vec![Ok("a"), Err(2), Err(3)],
+        unaffected: true,
+    };
+    assert_eq!(x.transpose(), Err(2));
+}
+
+#[test]
+fn transpose_named() {
+    #[derive(Functor, Debug, PartialEq)]
+    #[functor(K as keys, V as values, transpose)]
+    struct Entry<K, V> {
+        key: K,
+        value: V,
+    }
+
+    let x = Entry {
+        key: Some(1),
+        value: Ok::<_, ()>(2),
+    };
+    let x = x.transpose_keys().unwrap().transpose_values();
+    assert_eq!(x, Ok(Entry { key: 1, value: 2 }));
+}
+
+#[test]
+fn transpose_is_opt_in() {
+    // Without the `transpose` option, the type is free to define a `transpose` of its own.
+    #[derive(Functor, Debug, PartialEq)]
+    struct Matrix<T>([[T; 2]; 2]);
+
+    impl<T: Copy> Matrix<T> {
+        fn transpose(self) -> Self {
+            let [[a, b], [c, d]] = self.0;
+            Matrix([[a, c], [b, d]])
+        }
+    }
+
+    let x = Matrix([[1, 2], [3, 4]]);
+    assert_eq!(x.fmap(|x| x * 2).transpose(), Matrix([[2, 6], [4, 8]]));
+}
+
+/// Returns `Pending` the given number of times, recording the order in which the futures finish.
+async fn after(polls: usize, value: u64, log: &RefCell<Vec<u64>>) -> u64 {
+    let mut remaining = polls;
+    std::future::poll_fn(|cx| {
+        if remaining == 0 {
+            return Poll::Ready(());
+        }
+        remaining -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await;
+    log.borrow_mut().push(value);
+    value
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn join_all_std() {
+    let log = RefCell::new(Vec::new());
+    let x = vec![after(2, 1, &log), after(0, 2, &log), after(1, 3, &log)];
+
+    assert_eq!(block_on(x.join_all()), vec![1, 2, 3]);
+    assert_eq!(log.into_inner(), vec![2, 3, 1]);
+}
+
+#[test]
+fn join_all_derived() {
+    #[derive(Functor, Debug, PartialEq)]
+    enum Tree<T> {
+        Leaf(T),
+        Node(Vec<Tree<T>>),
+    }
+
+    let log = RefCell::new(Vec::new());
+    let x = Tree::Node(vec![
+        Tree::Leaf(after(1, 1, &log)),
+        Tree::Node(vec![Tree::Leaf(after(0, 2, &log))]),
+    ]);
+
+    assert_eq!(
+        block_on(x.join_all()),
+        Tree::Node(vec![Tree::Leaf(1), Tree::Node(vec![Tree::Leaf(2)])])
+    );
+    assert_eq!(log.into_inner(), vec![2, 1]);
+}
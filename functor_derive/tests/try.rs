@@ -1,3 +1,5 @@
+#![cfg(feature = "alloc")]
+
 use functor_derive::Functor;
 use std::any::{Any, TypeId};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -0,0 +1,358 @@
+use crate::generate_map::generate_map_from_type;
+use crate::parse_attribute::Attribute;
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::{abort, abort_call_site};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, Data, DeriveInput, Fields, GenericArgument, GenericParam, Path, PathArguments,
+    ReturnType, Type,
+};
+
+/// Generates the base functor `{Name}F<R>` of the recursive type `Name`, with every recursive position replaced by `R`,
+/// together with the conversions between them and the recursion schemes built on these.
+pub fn derive_base_functor(input: DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let base_name = format_ident!("{name}F");
+    let rec = format_ident!("R");
+
+    if input.generics.type_params().any(|param| param.ident == rec) {
+        abort!(
+            rec,
+            "The type parameter `R` is reserved for the recursive positions of `{}`.",
+            base_name
+        )
+    }
+    if matches!(input.data, Data::Union(_)) {
+        abort_call_site!("Deriving BaseFunctor on unions is unsupported.")
+    }
+
+    // The base functor has the same shape, with `R` in place of `Box<Name>` and `Name`.
+    let args = generic_args(&input);
+    let mut base = input.clone();
+    base.ident = base_name.clone();
+    base.attrs = vec![
+        parse_quote!(#[derive(::functor_derive::Functor)]),
+        parse_quote!(#[functor(#rec)]),
+    ];
+    base.generics.params.push(parse_quote!(#rec));
+    let rec_type: Type = parse_quote!(#rec);
+    for fields in fields_mut(&mut base.data) {
+        for field in fields.iter_mut() {
+            field.attrs.clear();
+            replace_recursion(&mut field.ty, name, &args, &rec_type, false);
+        }
+    }
+    if let Data::Enum(enm) = &mut base.data {
+        for variant in &mut enm.variants {
+            variant.attrs.clear();
+        }
+    }
+
+    let layer = |rec: TokenStream| quote!(#base_name<#(#args,)* #rec>);
+    let layer_of_self = layer(quote!(Self));
+    let layer_of_a = layer(quote!(__A));
+    let layer_of_s = layer(quote!(__S));
+    let layer_of_pair = layer(quote!((Self, __A)));
+
+    let project = generate_conversion(
+        &input,
+        &quote!(self),
+        &quote!(Self),
+        &quote!(#base_name),
+        &quote!(Self),
+        true,
+    );
+    let embed = generate_conversion(
+        &input,
+        &quote!(layer),
+        &quote!(#base_name),
+        &quote!(Self),
+        &quote!(Self),
+        false,
+    );
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote!(
+        #[allow(dead_code)]
+        #base
+
+        #[allow(dead_code)]
+        #[automatically_derived]
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Unfolds one layer of this value, leaving its children in the recursive positions.
+            pub fn project(self) -> #layer_of_self {
+                use ::functor_derive::*;
+                #project
+            }
+
+            /// Folds one layer back into a value.
+            pub fn embed(layer: #layer_of_self) -> Self {
+                use ::functor_derive::*;
+                #embed
+            }
+
+            /// Folds this value bottom-up, passing each layer with its children already folded to `alg`.
+            ///
+            /// This recurses on the call stack, which can overflow for very deep values.
+            pub fn cata<__A>(self, alg: impl Fn(#layer_of_a) -> __A) -> __A {
+                self.__cata(&alg)
+            }
+
+            fn __cata<__A>(self, alg: &impl Fn(#layer_of_a) -> __A) -> __A {
+                alg(self.project().fmap(|child| child.__cata(alg)))
+            }
+
+            /// Unfolds a value top-down from `seed`, where `coalg` produces each layer with the seeds of its children.
+            ///
+            /// This recurses on the call stack, which can overflow for very deep values.
+            pub fn ana<__S>(seed: __S, coalg: impl Fn(__S) -> #layer_of_s) -> Self {
+                Self::__ana(seed, &coalg)
+            }
+
+            fn __ana<__S>(seed: __S, coalg: &impl Fn(__S) -> #layer_of_s) -> Self {
+                Self::embed(coalg(seed).fmap(|seed| Self::__ana(seed, coalg)))
+            }
+
+            /// Folds this value bottom-up like `cata`, passing each child alongside its folded result.
+            ///
+            /// This recurses on the call stack, which can overflow for very deep values. Every child is cloned in full
+            /// before it is folded, which takes time proportional to the size of the value times its depth.
+            pub fn para<__A>(self, alg: impl Fn(#layer_of_pair) -> __A) -> __A
+            where
+                // The bound is higher-ranked over an unused lifetime, so that it is only checked where `para` is
+                // called. A plain `Self: Clone` on a type without generics is checked right away, and fails the
+                // derive if the type is not `Clone`, until trivial bounds are allowed. Higher-ranked bounds are not
+                // considered trivial, see https://github.com/rust-lang/rust/issues/48214.
+                for<'__p> Self: ::core::clone::Clone,
+            {
+                self.__para(&alg)
+            }
+
+            fn __para<__A>(self, alg: &impl Fn(#layer_of_pair) -> __A) -> __A
+            where
+                // Deferred to the call site like the bound of `para`.
+                for<'__p> Self: ::core::clone::Clone,
+            {
+                alg(self.project().fmap(|child| (::core::clone::Clone::clone(&child), child.__para(alg))))
+            }
+        }
+    )
+}
+
+pub fn fields_mut(data: &mut Data) -> Vec<&mut Fields> {
+    match data {
+        Data::Struct(strct) => vec![&mut strct.fields],
+        Data::Enum(enm) => enm.variants.iter_mut().map(|v| &mut v.fields).collect(),
+        Data::Union(_) => vec![],
+    }
+}
+
+/// Converts `value` between the recursive type and its base functor, which only differ where the recursive type is
+/// boxed. Projecting moves out of each box, embedding puts each child of type `child` back in a box.
+pub fn generate_conversion(
+    input: &DeriveInput,
+    value: &TokenStream,
+    source: &TokenStream,
+    target: &TokenStream,
+    child: &TokenStream,
+    is_project: bool,
+) -> TokenStream {
+    let boxed = format_ident!("__Boxed");
+    let boxed_type: Type = parse_quote!(#boxed);
+    let attribute = Attribute::default();
+    let args = generic_args(input);
+
+    let convert = |typ: &Type, field: TokenStream| {
+        let mut typ = typ.clone();
+        let Some(box_path) = replace_recursion(&mut typ, &input.ident, &args, &boxed_type, true)
+        else {
+            return field;
+        };
+        let map = generate_map_from_type(&typ, &boxed, &field, false, &attribute)
+            .unwrap_or_else(|| abort!(typ, "Cannot convert the recursive positions of this type."))
+            .0;
+        let unbox = if is_project {
+            quote!(&|__b: #box_path<#child>| *__b)
+        } else {
+            quote!(&|__b: #child| #box_path::new(__b))
+        };
+        quote!({
+            let __f = #unbox;
+            #map
+        })
+    };
+
+    let convert_fields = |fields: &Fields, path: TokenStream| match fields {
+        Fields::Named(fields) => {
+            let names = fields
+                .named
+                .iter()
+                .map(|field| field.ident.as_ref().unwrap())
+                .collect::<Vec<_>>();
+            let values = fields
+                .named
+                .iter()
+                .zip(&names)
+                .map(|(field, name)| convert(&field.ty, quote!(#name)));
+            quote!(#source #path { #(#names),* } => #target #path { #(#names: #values),* })
+        }
+        Fields::Unnamed(fields) => {
+            let names = (0..fields.unnamed.len())
+                .map(|i| format_ident!("v{i}"))
+                .collect::<Vec<_>>();
+            let values = fields
+                .unnamed
+                .iter()
+                .zip(&names)
+                .map(|(field, name)| convert(&field.ty, quote!(#name)));
+            quote!(#source #path (#(#names),*) => #target #path (#(#values),*))
+        }
+        Fields::Unit => quote!(#source #path => #target #path),
+    };
+
+    let arms = match &input.data {
+        Data::Struct(strct) => vec![convert_fields(&strct.fields, quote!())],
+        Data::Enum(enm) => enm
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_name = &variant.ident;
+                convert_fields(&variant.fields, quote!(::#variant_name))
+            })
+            .collect(),
+        Data::Union(_) => unreachable!(),
+    };
+
+    quote!(match #value { #(#arms),* })
+}
+
+/// Returns the generic arguments with which `input` refers to itself, i.e. its parameters.
+pub fn generic_args(input: &DeriveInput) -> Vec<TokenStream> {
+    input
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(l) => {
+                let lifetime = &l.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Type(t) => {
+                let ident = &t.ident;
+                quote!(#ident)
+            }
+            GenericParam::Const(c) => {
+                let ident = &c.ident;
+                quote!(#ident)
+            }
+        })
+        .collect()
+}
+
+/// Returns whether `typ` is the recursive type `name`, written as `Self` or by name with the generic arguments `args`.
+/// Other instantiations, like `Rose<u8>` inside `Rose<T>`, are ordinary fields.
+fn is_recursive(typ: &Type, name: &Ident, args: &[TokenStream]) -> bool {
+    let Type::Path(path) = typ else {
+        return false;
+    };
+    if path.qself.is_some() || path.path.segments.len() != 1 {
+        return false;
+    }
+    let segment = &path.path.segments[0];
+    if segment.ident == "Self" {
+        return true;
+    }
+    segment.ident == *name
+        && match &segment.arguments {
+            PathArguments::AngleBracketed(arguments) => arguments
+                .args
+                .iter()
+                .map(|arg| arg.to_token_stream().to_string())
+                .eq(args.iter().map(|arg| arg.to_string())),
+            PathArguments::None => args.is_empty(),
+            PathArguments::Parenthesized(_) => false,
+        }
+}
+
+/// Returns the path of the `Box` if `typ` is a boxed recursive type, like `Box<Name>`.
+fn boxed_recursion(typ: &Type, name: &Ident, args: &[TokenStream]) -> Option<Path> {
+    let Type::Path(path) = typ else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if path.qself.is_some() || segment.ident != "Box" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first() {
+        Some(GenericArgument::Type(inner))
+            if arguments.args.len() == 1 && is_recursive(inner, name, args) =>
+        {
+            let mut box_path = path.path.clone();
+            box_path.segments.last_mut()?.arguments = PathArguments::None;
+            Some(box_path)
+        }
+        _ => None,
+    }
+}
+
+/// Replaces the boxed recursive positions in `typ` by `replacement`, as well as the unboxed ones unless `boxed_only`.
+/// Returns the path of a replaced `Box` if there was one, or else the path `Box` if anything was replaced.
+pub fn replace_recursion(
+    typ: &mut Type,
+    name: &Ident,
+    args: &[TokenStream],
+    replacement: &Type,
+    boxed_only: bool,
+) -> Option<Path> {
+    if let Some(box_path) = boxed_recursion(typ, name, args) {
+        *typ = replacement.clone();
+        return Some(box_path);
+    }
+    if is_recursive(typ, name, args) {
+        if boxed_only {
+            return None;
+        }
+        *typ = replacement.clone();
+        return Some(parse_quote!(Box));
+    }
+
+    let mut replaced = None;
+    let mut replace = |typ: &mut Type| {
+        if let Some(box_path) = replace_recursion(typ, name, args, replacement, boxed_only) {
+            replaced.get_or_insert(box_path);
+        }
+    };
+    match typ {
+        Type::Array(array) => replace(&mut array.elem),
+        Type::Group(group) => replace(&mut group.elem),
+        Type::Paren(paren) => replace(&mut paren.elem),
+        Type::Slice(slice) => replace(&mut slice.elem),
+        Type::Tuple(tuple) => tuple.elems.iter_mut().for_each(replace),
+        Type::Path(path) => {
+            for segment in &mut path.path.segments {
+                match &mut segment.arguments {
+                    PathArguments::AngleBracketed(args) => {
+                        for arg in &mut args.args {
+                            if let GenericArgument::Type(typ) = arg {
+                                replace(typ);
+                            }
+                        }
+                    }
+                    PathArguments::Parenthesized(args) => {
+                        args.inputs.iter_mut().for_each(&mut replace);
+                        if let ReturnType::Type(_, typ) = &mut args.output {
+                            replace(typ);
+                        }
+                    }
+                    PathArguments::None => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    replaced
+}
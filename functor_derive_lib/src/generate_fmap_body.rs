@@ -1,18 +1,26 @@
-use crate::generate_map::generate_map_from_type;
+use crate::generate_map::{generate_fill, generate_map_from_type, generate_map_once};
+use crate::parse_attribute::Attribute;
 use proc_macro2::{Ident, TokenStream};
-use proc_macro_error::abort_call_site;
+use proc_macro_error::{abort, abort_call_site};
 use quote::{format_ident, quote};
-use syn::{Data, DataEnum, DataStruct, Fields, Index};
+use syn::{Data, DataEnum, DataStruct, Fields, Index, Type};
 
+/// Generates the body that maps `source`, or `self` if none is given, by destructuring it and rebuilding it as `def_path`.
 pub fn generate_fmap_body(
     data: &Data,
-    def_name: &Ident,
+    def_path: &TokenStream,
+    source: Option<&TokenStream>,
     functor_param: &Ident,
     is_try: bool,
+    attribute: &Attribute,
 ) -> Option<TokenStream> {
     match data {
-        Data::Struct(strct) => generate_fmap_body_struct(strct, functor_param, def_name, is_try),
-        Data::Enum(enm) => generate_fmap_body_enum(enm, functor_param, def_name, is_try),
+        Data::Struct(strct) => {
+            generate_fmap_body_struct(strct, functor_param, def_path, source, is_try, attribute)
+        }
+        Data::Enum(enm) => {
+            generate_fmap_body_enum(enm, functor_param, def_path, source, is_try, attribute)
+        }
         Data::Union(_) => abort_call_site!("Deriving Functor on unions is unsupported."),
     }
 }
@@ -20,9 +28,16 @@ pub fn generate_fmap_body(
 fn generate_fmap_body_enum(
     enm: &DataEnum,
     functor_param: &Ident,
-    def_name: &Ident,
+    def_path: &TokenStream,
+    source: Option<&TokenStream>,
     is_try: bool,
+    attribute: &Attribute,
 ) -> Option<TokenStream> {
+    // Variants of `self` are matched through `Self`.
+    let (source, pattern_path) = match source {
+        Some(source) => (source.clone(), def_path.clone()),
+        None => (quote!(self), quote!(Self)),
+    };
     let variants = enm.variants.iter().map(|variant| {
         let variant_name = &variant.ident;
         let field = match &variant.fields {
@@ -38,13 +53,14 @@ fn generate_fmap_body_enum(
                         functor_param,
                         &quote!(#field_name),
                         is_try,
+                        attribute,
                     )?
                     .0;
                     Some(quote!(#field_name: #field))
                 }).collect::<Option<Vec<_>>>()?;
 
-                quote!(Self::#variant_name { #(#names),* } => {
-                    #def_name::#variant_name {
+                quote!(#pattern_path::#variant_name { #(#names),* } => {
+                    #def_path::#variant_name {
                         #(#fields),*
                     }
                 })
@@ -54,23 +70,26 @@ fn generate_fmap_body_enum(
                     .map(|i| format_ident!("v{i}"))
                     .take(fields.unnamed.len());
                 let fields = fields.unnamed.iter().zip(names.clone()).map(|(field, i)| {
-                    generate_map_from_type(&field.ty, functor_param, &quote!(#i), is_try).map(|(v, _)| v)
+                    generate_map_from_type(&field.ty, functor_param, &quote!(#i), is_try, attribute).map(|(v, _)| v)
                 }).collect::<Option<Vec<_>>>()?;
-                quote!(Self::#variant_name(#(#names),*) => #def_name::#variant_name(#(#fields),*))
+                quote!(#pattern_path::#variant_name(#(#names),*) => #def_path::#variant_name(#(#fields),*))
             }
-            Fields::Unit => quote!(Self::#variant_name => #def_name::#variant_name),
+            Fields::Unit => quote!(#pattern_path::#variant_name => #def_path::#variant_name),
         };
         Some(field)
     }).collect::<Option<Vec<_>>>()?;
-    Some(quote!(match self {#(#variants),*}))
+    Some(quote!(match #source {#(#variants),*}))
 }
 
 fn generate_fmap_body_struct(
     strct: &DataStruct,
     functor_param: &Ident,
-    def_name: &Ident,
+    def_path: &TokenStream,
+    source: Option<&TokenStream>,
     is_try: bool,
+    attribute: &Attribute,
 ) -> Option<TokenStream> {
+    let source = source.cloned().unwrap_or_else(|| quote!(self));
     match &strct.fields {
         Fields::Named(fields) => {
             let fields = fields
@@ -81,14 +100,15 @@ fn generate_fmap_body_struct(
                     let field = generate_map_from_type(
                         &field.ty,
                         functor_param,
-                        &quote!(self.#field_name),
+                        &quote!(#source.#field_name),
                         is_try,
+                        attribute,
                     )?
                     .0;
                     Some(quote!(#field_name: #field))
                 })
                 .collect::<Option<Vec<_>>>()?;
-            Some(quote!(#def_name{#(#fields),*}))
+            Some(quote!(#def_path{#(#fields),*}))
         }
         Fields::Unnamed(s) => {
             let fields = s
@@ -98,13 +118,164 @@ fn generate_fmap_body_struct(
                 .map(|(i, field)| {
                     let i = Index::from(i);
                     Some(
-                        generate_map_from_type(&field.ty, functor_param, &quote!(self.#i), is_try)?
-                            .0,
+                        generate_map_from_type(
+                            &field.ty,
+                            functor_param,
+                            &quote!(#source.#i),
+                            is_try,
+                            attribute,
+                        )?
+                        .0,
                     )
                 })
                 .collect::<Option<Vec<_>>>()?;
-            Some(quote!(#def_name(#(#fields),*)))
+            Some(quote!(#def_path(#(#fields),*)))
         }
         Fields::Unit => abort_call_site!("Cannot derive `Functor` for Unit Structs."),
     }
 }
+
+/// Generates the body of `fmap_once`, which maps `self` with a function that is called exactly once.
+/// Aborts if some variant does not hold `functor_param` exactly once, outside of any container.
+pub fn generate_fmap_once_body(
+    data: &Data,
+    def_name: &Ident,
+    functor_param: &Ident,
+    attribute: &Attribute,
+) -> TokenStream {
+    // Maps the fields of a struct or variant, which are bound to `names`, and checks that they hold the parameter once.
+    let map_fields = |fields: &Fields, names: &[TokenStream], name: &Ident| {
+        let mut count = 0;
+        let maps = fields
+            .iter()
+            .zip(names)
+            .map(|(field, value)| {
+                let (map, n) = generate_map_once(&field.ty, functor_param, value, attribute)
+                    .unwrap_or_else(|typ| {
+                        abort!(
+                            typ,
+                            "`fmap_once` cannot map `{}` in this type, as it may hold `{}` more than once.",
+                            functor_param,
+                            functor_param
+                        )
+                    });
+                count += n;
+                map
+            })
+            .collect::<Vec<_>>();
+        if count != 1 {
+            abort!(
+                name,
+                "`fmap_once` requires `{}` to occur exactly once in `{}`, but it occurs {} times.",
+                functor_param,
+                name,
+                count
+            )
+        }
+        maps
+    };
+
+    match data {
+        Data::Struct(strct) => {
+            let names = field_names(&strct.fields);
+            let values = names
+                .iter()
+                .map(|name| quote!(self.#name))
+                .collect::<Vec<_>>();
+            let maps = map_fields(&strct.fields, &values, def_name);
+            construct(&quote!(#def_name), &strct.fields, &names, &maps)
+        }
+        Data::Enum(enm) => {
+            let arms = enm.variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let names = field_names(&variant.fields);
+                let bindings = (0..names.len())
+                    .map(|i| {
+                        let binding = format_ident!("v{i}");
+                        quote!(#binding)
+                    })
+                    .collect::<Vec<_>>();
+                let maps = map_fields(&variant.fields, &bindings, variant_name);
+                let pattern = construct(
+                    &quote!(Self::#variant_name),
+                    &variant.fields,
+                    &names,
+                    &bindings,
+                );
+                let value = construct(
+                    &quote!(#def_name::#variant_name),
+                    &variant.fields,
+                    &names,
+                    &maps,
+                );
+                quote!(#pattern => #value)
+            });
+            quote!(match self { #(#arms),* })
+        }
+        Data::Union(_) => abort_call_site!("Deriving Functor on unions is unsupported."),
+    }
+}
+
+/// Generates the body of `from_fn`, which constructs a struct by calling `__f` for every occurrence of `functor_param`,
+/// together with the types that are filled with their default. Aborts for enums, and for fields that hold
+/// `functor_param` a varying number of times.
+pub fn generate_from_fn_body<'a>(
+    data: &'a Data,
+    functor_param: &Ident,
+) -> (TokenStream, Vec<&'a Type>) {
+    let Data::Struct(strct) = data else {
+        abort_call_site!("`from_fn` can only be derived for structs, as it could construct any variant of an enum.")
+    };
+
+    let names = field_names(&strct.fields);
+    let mut defaults = Vec::new();
+    let fills = strct
+        .fields
+        .iter()
+        .zip(&names)
+        .map(|(field, name)| {
+            let name = name.to_string();
+            let mut path = vec![quote!(::functor_derive::SlotStep::Field(#name))];
+            generate_fill(&field.ty, functor_param, &mut path, &mut defaults).unwrap_or_else(|typ| {
+                abort!(
+                    typ,
+                    "`from_fn` cannot fill `{}` in this type, as the number of values it holds is not fixed.",
+                    functor_param
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    (
+        construct(&quote!(Self), &strct.fields, &names, &fills),
+        defaults,
+    )
+}
+
+/// Returns the names by which the fields are accessed, which are their indices for tuple structs and variants.
+fn field_names(fields: &Fields) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let i = Index::from(i);
+                quote!(#i)
+            }
+        })
+        .collect()
+}
+
+/// Constructs, or matches, `path` with the given values of its fields.
+fn construct(
+    path: &TokenStream,
+    fields: &Fields,
+    names: &[TokenStream],
+    values: &[TokenStream],
+) -> TokenStream {
+    match fields {
+        Fields::Named(_) => quote!(#path { #(#names: #values),* }),
+        Fields::Unnamed(_) => quote!(#path(#(#values),*)),
+        Fields::Unit => quote!(#path),
+    }
+}
@@ -1,17 +1,32 @@
+use crate::map::map_type;
+use crate::parse_attribute::{is_declared, Attribute, Collisions, Projection};
 use itertools::Itertools;
-use proc_macro2::{Ident, TokenStream};
-use proc_macro_error::abort_call_site;
-use quote::{format_ident, quote};
-use syn::{GenericArgument, Index, PathArguments, ReturnType, Type, TypeParamBound, TypePath};
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use proc_macro_error::{abort, abort_call_site};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, GenericArgument, Index, Path, PathArguments, PathSegment, ReturnType, Type,
+    TypeParamBound, TypePath, TypeTraitObject, WherePredicate,
+};
 
 pub fn generate_map_from_type(
     typ: &Type,
     param: &Ident,
     field: &TokenStream,
     is_try: bool,
+    attribute: &Attribute,
 ) -> Option<(TokenStream, bool)> {
     let stream = match typ {
-        Type::Path(path) => return generate_map_from_path(path, param, field, is_try),
+        Type::Path(path) => return generate_map_from_path(path, param, field, is_try, attribute),
+        // Tuples up to 12 elements implement the positional traits.
+        Type::Tuple(tuple) if tuple.elems.len() <= 12 => {
+            let positions = tuple
+                .elems
+                .iter()
+                .enumerate()
+                .filter(|(_, typ)| type_contains_param(typ, param));
+            return generate_positional_maps(field, positions, None, param, is_try, attribute);
+        }
         Type::Tuple(tuple) => {
             let positions = tuple
                 .elems
@@ -19,7 +34,8 @@ pub fn generate_map_from_type(
                 .enumerate()
                 .map(|(i, x)| {
                     let i = Index::from(i);
-                    let field = generate_map_from_type(x, param, &quote!(#field.#i), is_try)?.0;
+                    let field =
+                        generate_map_from_type(x, param, &quote!(#field.#i), is_try, attribute)?.0;
                     Some(quote!(#field,))
                 })
                 .collect::<Option<Vec<_>>>()?;
@@ -27,9 +43,11 @@ pub fn generate_map_from_type(
         }
         Type::Array(array) => {
             if type_contains_param(typ, param) {
-                let map = generate_map_from_type(&array.elem, param, &quote!(__v), is_try)?.0;
+                let map =
+                    generate_map_from_type(&array.elem, param, &quote!(__v), is_try, attribute)?.0;
                 if is_try {
-                    quote!(#field.try_fmap(|__v| Ok(#map))?)
+                    let map = wrap_ok(map);
+                    quote!(#field.try_fmap(|__v| #map)?)
                 } else {
                     quote!(#field.map(|__v| #map))
                 }
@@ -37,19 +55,22 @@ pub fn generate_map_from_type(
                 quote!(#field)
             }
         }
-        Type::Paren(p) => generate_map_from_type(&p.elem, param, field, is_try)?.0,
+        Type::Paren(p) => generate_map_from_type(&p.elem, param, field, is_try, attribute)?.0,
+        Type::Group(g) => generate_map_from_type(&g.elem, param, field, is_try, attribute)?.0,
+        // Slices only occur behind pointers such as `Box<[T]>`, whose impls map the elements.
+        Type::Slice(slice) => {
+            return generate_map_from_type(&slice.elem, param, field, is_try, attribute)
+        }
         // We cannot possibly map these, but passing them through is fine.
         Type::BareFn(_)
         | Type::Reference(_)
         | Type::Ptr(_)
-        | Type::Slice(_)
         | Type::Never(_)
         | Type::Macro(_)
         | Type::Infer(_)
         | Type::ImplTrait(_)
         | Type::TraitObject(_)
-        | Type::Verbatim(_)
-        | Type::Group(_) => {
+        | Type::Verbatim(_) => {
             if type_contains_param(typ, param) {
                 return None;
             } else {
@@ -67,12 +88,37 @@ fn generate_map_from_path(
     param: &Ident,
     field: &TokenStream,
     is_try: bool,
+    attribute: &Attribute,
 ) -> Option<(TokenStream, bool)> {
     // Simply return the field if it does not contain the parameter `param`.
     if !type_contains_param(&Type::Path(path.clone()), param) {
         return Some((quote!(#field), false));
     }
 
+    // Markers such as `PhantomData<fn(T) -> T>` hold no values, so they are rebuilt whatever their argument is.
+    if is_phantom_data(path) {
+        return Some((
+            quote!({
+                let _ = #field;
+                ::core::marker::PhantomData
+            }),
+            false,
+        ));
+    }
+
+    // Projections such as `T::Id` are mapped by the function the user provided for them.
+    if let Some((projected, _)) = projection_of(path) {
+        if projected == param {
+            let via = &find_projection(attribute, param, path)?.via;
+            return Some((quote!(#via::<#param, __B>(#field)), false));
+        }
+    }
+
+    // Boxed iterators and closures are adapted lazily, if the user opted in.
+    if let Some((obj, boxed)) = boxed(path) {
+        return generate_map_from_boxed(path, obj, boxed, param, field, is_try, attribute);
+    }
+
     // If the path consists of exactly one segment, then it must be the param.
     match path.path.segments.iter().exactly_one() {
         Ok(segment) if &segment.ident == param => {
@@ -85,15 +131,23 @@ fn generate_map_from_path(
         _ => {}
     }
 
-    let Some(last_segment) = path.path.segments.last() else {
-        unreachable!()
-    };
+    // The parameter can only be mapped through the generic arguments of the last segment.
+    if path.qself.is_some() || !path_prefix_is_free_of_param(&path.path, param) {
+        return None;
+    }
+
+    let last_segment = path.path.segments.last()?;
 
     let PathArguments::AngleBracketed(args) = &last_segment.arguments else {
-        unreachable!()
+        return None;
     };
 
-    let mut tokens = quote!(#field);
+    // Bindings such as `Item = T` do not correspond to a positional parameter.
+    if args.args.iter().any(
+        |arg| matches!(arg, GenericArgument::AssocType(assoc) if type_contains_param(&assoc.ty, param)),
+    ) {
+        return None;
+    }
 
     let enumerated_type_params = args
         .args
@@ -106,34 +160,906 @@ fn generate_map_from_path(
                 None
             }
         })
-        .filter(|(_, typ)| type_contains_param(typ, param));
+        .filter(|(_, typ)| type_contains_param(typ, param))
+        .collect::<Vec<_>>();
 
-    // Loop over all arguments that contain `param`
-    for (type_arg_idx, type_arg) in enumerated_type_params {
-        let (map, is_end) = generate_map_from_type(type_arg, param, &quote!(v), is_try)?;
+    // Types that only implement the public `Functor` trait are mapped through it.
+    if find_functor(attribute, &path.path) {
+        let Ok((_, type_arg)) = enumerated_type_params.into_iter().exactly_one() else {
+            return None;
+        };
+        let (map, is_end) = generate_map_from_type(type_arg, param, &quote!(v), is_try, attribute)?;
+        let stream = match (is_try, is_end) {
+            (false, true) => quote!(::functor_derive::Functor::fmap(#field, __f)),
+            (false, false) => quote!(::functor_derive::Functor::fmap(#field, |v| #map)),
+            (true, true) => quote!(::functor_derive::Functor::try_fmap(#field, __f)?),
+            (true, false) => {
+                let map = wrap_ok(map);
+                quote!(::functor_derive::Functor::try_fmap(#field, |v| #map)?)
+            }
+        };
+        return Some((stream, false));
+    }
 
-        if is_try {
-            let map_ident = format_ident!("__try_fmap_{type_arg_idx}_ref");
-            if is_end {
-                tokens.extend(quote!(.#map_ident(__f)?))
-            } else {
-                tokens.extend(quote!(.#map_ident(&|v| { Ok(#map) })?))
+    // Foreign types cannot implement the positional traits, so they are mapped through their mirror.
+    let via = attribute
+        .remotes
+        .iter()
+        .find(|remote| is_declared(&remote.ty, &path.path))
+        .map(|remote| &remote.via);
+
+    if enumerated_type_params
+        .iter()
+        .any(|(idx, _)| is_hasher_position(&last_segment.ident, *idx))
+    {
+        return None;
+    }
+
+    let maps_keys = enumerated_type_params
+        .iter()
+        .any(|(idx, _)| is_key_position(&last_segment.ident, *idx));
+
+    let resolves_collisions = match attribute.collisions {
+        Collisions::KeepLast | Collisions::Panic => false,
+        Collisions::Strict => is_try,
+        Collisions::Merge(_) => true,
+    };
+    if maps_keys && resolves_collisions {
+        return Some((
+            generate_map_resolving_collisions(path, param, field, is_try, attribute)?,
+            false,
+        ));
+    }
+
+    // Without an error to return, `fmap` panics under the strict policy too.
+    if maps_keys && matches!(attribute.collisions, Collisions::Panic | Collisions::Strict) {
+        let (map, _) = generate_positional_maps(
+            &quote!(__keys),
+            enumerated_type_params.into_iter(),
+            via,
+            param,
+            is_try,
+            attribute,
+        )?;
+        let message = format!(
+            "Two keys of a `{}` were mapped to the same key.",
+            last_segment.ident
+        );
+        return Some((
+            quote!({
+                let __keys = #field;
+                let __len = __keys.len();
+                let __mapped = #map;
+                assert!(__mapped.len() == __len, #message);
+                __mapped
+            }),
+            false,
+        ));
+    }
+
+    generate_positional_maps(
+        field,
+        enumerated_type_params.into_iter(),
+        via,
+        param,
+        is_try,
+        attribute,
+    )
+}
+
+/// Rebuilds a map or set entry by entry, so that colliding keys can be reported or merged as the policy requires.
+fn generate_map_resolving_collisions(
+    path: &TypePath,
+    param: &Ident,
+    field: &TokenStream,
+    is_try: bool,
+    attribute: &Attribute,
+) -> Option<TokenStream> {
+    let segment = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let mut types = args.args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(typ) => Some(typ),
+        _ => None,
+    });
+    let key = types.next()?;
+    let value = if segment.ident == "HashMap" || segment.ident == "BTreeMap" {
+        Some(types.next()?)
+    } else {
+        None
+    };
+
+    // Refer to the collection the way the field does, and reuse the hasher of hash collections.
+    let mut collection = path.path.clone();
+    collection.segments.last_mut()?.arguments = PathArguments::None;
+    let new = if segment.ident == "HashMap" || segment.ident == "HashSet" {
+        quote!(#collection::with_hasher(::core::clone::Clone::clone(__keys.hasher())))
+    } else {
+        quote!(#collection::new())
+    };
+
+    let map_key = generate_map_from_type(key, param, &quote!(__k), is_try, attribute)?.0;
+    // Name the error type, as it cannot be inferred when returning from a closure.
+    let collision = quote!(
+        return ::core::result::Result::Err::<_, __E>(::core::convert::From::from(
+            ::functor_derive::KeyCollision { key: __k }
+        ))
+    );
+
+    let (pattern, map_value, insert) = match value {
+        Some(value) => {
+            let map_value =
+                generate_map_from_type(value, param, &quote!(__v), is_try, attribute)?.0;
+            let insert = match &attribute.collisions {
+                Collisions::Merge(merge) => quote!(
+                    let __v = match __mapped.remove(&__k) {
+                        ::core::option::Option::Some(__first) => #merge(__first, __v),
+                        ::core::option::Option::None => __v,
+                    };
+                    __mapped.insert(__k, __v);
+                ),
+                _ => quote!(
+                    if __mapped.contains_key(&__k) {
+                        #collision
+                    }
+                    __mapped.insert(__k, __v);
+                ),
+            };
+            (quote!((__k, __v)), quote!(let __v = #map_value;), insert)
+        }
+        None => {
+            let insert = match &attribute.collisions {
+                Collisions::Merge(_) => quote!(__mapped.insert(__k);),
+                _ => quote!(
+                    if __mapped.contains(&__k) {
+                        #collision
+                    }
+                    __mapped.insert(__k);
+                ),
+            };
+            (quote!(__k), quote!(), insert)
+        }
+    };
+
+    Some(quote!({
+        let __keys = #field;
+        let mut __mapped = #new;
+        for #pattern in __keys {
+            let __k = #map_key;
+            #map_value
+            #insert
+        }
+        __mapped
+    }))
+}
+
+/// Adds the key types that `try_fmap` reports collisions of under the strict policy, after mapping, for the maps and
+/// sets in `typ` whose keys contain the parameter `param`.
+pub fn collision_keys(typ: &Type, param: &Ident, keys: &mut Vec<Type>) {
+    if !type_contains_param(typ, param) {
+        return;
+    }
+
+    match typ {
+        Type::Path(path) if is_phantom_data(path) => {}
+        Type::Path(path) => {
+            let Some(segment) = path.path.segments.last() else {
+                return;
+            };
+            let PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return;
+            };
+            for (idx, arg) in args.args.iter().enumerate() {
+                let GenericArgument::Type(arg) = arg else {
+                    continue;
+                };
+                if is_key_position(&segment.ident, idx) && type_contains_param(arg, param) {
+                    let mut target = arg.clone();
+                    map_type(&mut target, param, &mut false);
+                    if !keys
+                        .iter()
+                        .any(|key| quote!(#key).to_string() == quote!(#target).to_string())
+                    {
+                        keys.push(target);
+                    }
+                }
+                collision_keys(arg, param, keys);
             }
+        }
+        Type::Array(array) => collision_keys(&array.elem, param, keys),
+        Type::Slice(slice) => collision_keys(&slice.elem, param, keys),
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .for_each(|typ| collision_keys(typ, param, keys)),
+        Type::Paren(paren) => collision_keys(&paren.elem, param, keys),
+        Type::Group(group) => collision_keys(&group.elem, param, keys),
+        _ => {}
+    }
+}
+
+/// Returns whether the generic argument at `idx` of the type named `ident` holds the keys of a map or the elements of a set,
+/// where mapping two of them to the same value drops one.
+fn is_key_position(ident: &Ident, idx: usize) -> bool {
+    ["HashMap", "HashSet", "BTreeMap", "BTreeSet"]
+        .iter()
+        .any(|name| ident == name)
+        && idx == 0
+}
+
+/// Returns whether `path` is a `PhantomData`, which can be rebuilt for any argument.
+fn is_phantom_data(path: &TypePath) -> bool {
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData")
+}
+
+/// Returns whether the generic argument at `idx` of the type named `ident` is the `BuildHasher` of a hash collection.
+fn is_hasher_position(ident: &Ident, idx: usize) -> bool {
+    (ident == "HashMap" && idx == 2) || (ident == "HashSet" && idx == 1)
+}
+
+/// Maps each of the given positions through the positional `FunctorN` traits, or through the associated functions of
+/// the mirror `via` for foreign types.
+fn generate_positional_maps<'a>(
+    field: &TokenStream,
+    positions: impl Iterator<Item = (usize, &'a Type)>,
+    via: Option<&Path>,
+    param: &Ident,
+    is_try: bool,
+    attribute: &Attribute,
+) -> Option<(TokenStream, bool)> {
+    let mut tokens = quote!(#field);
+
+    // Loop over all arguments that contain `param`
+    for (type_arg_idx, type_arg) in positions {
+        let (map, is_end) = generate_map_from_type(type_arg, param, &quote!(v), is_try, attribute)?;
+
+        let (map_ident, map) = if is_try {
+            (format_ident!("__try_fmap_{type_arg_idx}_ref"), wrap_ok(map))
         } else {
-            let map_ident = format_ident!("__fmap_{type_arg_idx}_ref");
-            if is_end {
-                tokens.extend(quote!(.#map_ident(__f)));
-            } else {
-                tokens.extend(quote!(.#map_ident(&|v| { #map })));
+            (format_ident!("__fmap_{type_arg_idx}_ref"), map)
+        };
+        let mapper = if is_end {
+            quote!(__f)
+        } else if attribute.lazy {
+            // The argument may map lazily too, so the composed function must own its copy of `__f`.
+            quote!(&{
+                let __f = ::core::clone::Clone::clone(__f);
+                move |v| {
+                    let __f = &__f;
+                    #map
+                }
+            })
+        } else {
+            quote!(&|v| { #map })
+        };
+
+        let question_mark = is_try.then(|| quote!(?));
+        tokens = match via {
+            Some(via) => quote!(#via::#map_ident(#tokens, #mapper)#question_mark),
+            None => quote!(#tokens.#map_ident(#mapper)#question_mark),
+        };
+    }
+
+    Some((tokens, false))
+}
+
+fn generate_map_from_boxed(
+    path: &TypePath,
+    obj: &TypeTraitObject,
+    boxed: Boxed,
+    param: &Ident,
+    field: &TokenStream,
+    is_try: bool,
+    attribute: &Attribute,
+) -> Option<(TokenStream, bool)> {
+    if !attribute.lazy {
+        return None;
+    }
+
+    let map = generate_map_from_type(boxed.value(), param, &quote!(v), is_try, attribute)?.0;
+
+    let mut target = Type::TraitObject(obj.clone());
+    map_type(&mut target, param, &mut false);
+
+    // Refer to `Box` the way the field does, as it need not be in scope in `no_std` crates.
+    let mut box_path = path.path.clone();
+    box_path.segments.last_mut()?.arguments = PathArguments::None;
+
+    // Errors cannot be reported lazily, so `try_fmap` drives the iterator to completion and calls the closure.
+    let stream = match (boxed, is_try) {
+        (Boxed::Iterator(_), true) => {
+            let map = wrap_ok(map);
+            quote!({
+                let __items = #field.map(|v| #map).collect::<::core::result::Result<#box_path<[_]>, __E>>()?;
+                #box_path::new(__items.into_vec().into_iter()) as #box_path<#target>
+            })
+        }
+        (Boxed::Iterator(_), false) => quote!({
+            let __f = ::core::clone::Clone::clone(__f);
+            #box_path::new(#field.map(move |v| {
+                let __f = &__f;
+                #map
+            })) as #box_path<#target>
+        }),
+        (Boxed::FnOnce(_), true) => quote!({
+            let v = (#field)();
+            let __value = #map;
+            #box_path::new(move || __value) as #box_path<#target>
+        }),
+        (Boxed::FnOnce(_), false) => quote!({
+            let __f = ::core::clone::Clone::clone(__f);
+            let __thunk = #field;
+            #box_path::new(move || {
+                let __f = &__f;
+                let v = __thunk();
+                #map
+            }) as #box_path<#target>
+        }),
+    };
+
+    Some((stream, false))
+}
+
+/// A boxed trait object whose values are mapped by adapting it, if the user opted in to lazy mapping.
+#[derive(Copy, Clone)]
+enum Boxed<'a> {
+    /// `Box<dyn Iterator<Item = T>>`, whose items are mapped by `Iterator::map`.
+    Iterator(&'a Type),
+    /// `Box<dyn FnOnce() -> T>`, whose result is mapped after calling it.
+    FnOnce(&'a Type),
+}
+
+impl<'a> Boxed<'a> {
+    fn value(self) -> &'a Type {
+        match self {
+            Boxed::Iterator(typ) | Boxed::FnOnce(typ) => typ,
+        }
+    }
+}
+
+/// Returns the trait object and the kind of adapter if `path` is of the form `Box<dyn Iterator<Item = T>>` or
+/// `Box<dyn FnOnce() -> T>`. Double-ended and exact-size iterators are recognised too, as the adapters preserve
+/// these traits.
+fn boxed(path: &TypePath) -> Option<(&TypeTraitObject, Boxed<'_>)> {
+    let (obj, segment) = boxed_trait(path)?;
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args)
+            if ["Iterator", "DoubleEndedIterator", "ExactSizeIterator"]
+                .iter()
+                .any(|name| segment.ident == name) =>
+        {
+            match args.args.iter().exactly_one() {
+                Ok(GenericArgument::AssocType(assoc)) if assoc.ident == "Item" => {
+                    Some((obj, Boxed::Iterator(&assoc.ty)))
+                }
+                _ => None,
+            }
+        }
+        PathArguments::Parenthesized(args)
+            if segment.ident == "FnOnce" && args.inputs.is_empty() =>
+        {
+            match &args.output {
+                ReturnType::Type(_, typ) => Some((obj, Boxed::FnOnce(typ))),
+                ReturnType::Default => None,
             }
         }
+        _ => None,
+    }
+}
+
+/// Returns the trait object and the last segment of its principal trait if `path` is of the form `Box<dyn Trait>`.
+fn boxed_trait(path: &TypePath) -> Option<(&TypeTraitObject, &PathSegment)> {
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Box" {
+        return None;
     }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let Ok(GenericArgument::Type(Type::TraitObject(obj))) = args.args.iter().exactly_one() else {
+        return None;
+    };
+    let Some(TypeParamBound::Trait(bound)) = obj.bounds.first() else {
+        return None;
+    };
+    Some((obj, bound.path.segments.last()?))
+}
 
-    Some((tokens, false))
+/// Returns the additional bounds, like `Send`, of the boxed iterators and closures in `typ` that contain the parameter
+/// `param`.
+/// When mapping lazily, the mapping function must satisfy these too.
+pub fn lazy_bounds(typ: &Type, param: &Ident, bounds: &mut Vec<TypeParamBound>) {
+    if !type_contains_param(typ, param) {
+        return;
+    }
+
+    match typ {
+        Type::Path(path) => {
+            if let Some((obj, boxed)) = boxed(path) {
+                for bound in obj.bounds.iter().skip(1) {
+                    let is_new = bounds
+                        .iter()
+                        .all(|other| quote!(#other).to_string() != quote!(#bound).to_string());
+                    if matches!(bound, TypeParamBound::Trait(_)) && is_new {
+                        bounds.push(bound.clone());
+                    }
+                }
+                lazy_bounds(boxed.value(), param, bounds);
+                return;
+            }
+
+            for segment in &path.path.segments {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    for arg in &args.args {
+                        if let GenericArgument::Type(typ) = arg {
+                            lazy_bounds(typ, param, bounds);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Array(array) => lazy_bounds(&array.elem, param, bounds),
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .for_each(|typ| lazy_bounds(typ, param, bounds)),
+        Type::Paren(paren) => lazy_bounds(&paren.elem, param, bounds),
+        Type::Group(group) => lazy_bounds(&group.elem, param, bounds),
+        _ => {}
+    }
+}
+
+/// Collects the predicates that the keys and set elements of types containing the parameter `param` need, before and
+/// after mapping, like `T: Hash + Eq` and `__B: Hash + Eq` for a `HashSet<T>`.
+pub fn key_bounds(typ: &Type, param: &Ident, predicates: &mut Vec<WherePredicate>) {
+    if !type_contains_param(typ, param) {
+        return;
+    }
+
+    match typ {
+        Type::Path(path) if is_phantom_data(path) => {}
+        Type::Path(path) => {
+            let Some(segment) = path.path.segments.last() else {
+                return;
+            };
+            let PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return;
+            };
+            for (idx, arg) in args.args.iter().enumerate() {
+                let GenericArgument::Type(arg) = arg else {
+                    continue;
+                };
+                // Mapping any argument of a map or set requires its keys to be bounded.
+                if let Some(bounds) = key_position_bounds(&segment.ident, idx) {
+                    let mut target = arg.clone();
+                    map_type(&mut target, param, &mut false);
+                    for bounded in [arg, &target] {
+                        let predicate: WherePredicate = parse_quote!(#bounded: #bounds);
+                        let tokens = quote!(#predicate).to_string();
+                        if !predicates
+                            .iter()
+                            .any(|predicate| quote!(#predicate).to_string() == tokens)
+                        {
+                            predicates.push(predicate);
+                        }
+                    }
+                }
+                key_bounds(arg, param, predicates);
+            }
+        }
+        Type::Array(array) => key_bounds(&array.elem, param, predicates),
+        Type::Slice(slice) => key_bounds(&slice.elem, param, predicates),
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .for_each(|typ| key_bounds(typ, param, predicates)),
+        Type::Paren(paren) => key_bounds(&paren.elem, param, predicates),
+        Type::Group(group) => key_bounds(&group.elem, param, predicates),
+        _ => {}
+    }
+}
+
+/// Returns the bounds that the generic argument at `idx` of the type named `ident` needs to be mapped, if it holds the
+/// keys of a map or the elements of a set or heap.
+fn key_position_bounds(ident: &Ident, idx: usize) -> Option<TokenStream> {
+    if idx != 0 {
+        return None;
+    }
+    if ident == "HashMap" || ident == "HashSet" {
+        Some(quote!(::core::hash::Hash + ::core::cmp::Eq))
+    } else if ident == "BTreeMap" || ident == "BTreeSet" || ident == "BinaryHeap" {
+        Some(quote!(::core::cmp::Ord))
+    } else {
+        None
+    }
+}
+
+/// Returns whether the type `path` was declared to implement only the public `Functor` trait.
+fn find_functor(attribute: &Attribute, path: &Path) -> bool {
+    attribute
+        .functors
+        .iter()
+        .any(|declared| is_declared(declared, path))
+}
+
+/// Turns a fallible `map` into an expression of type `Result`. A map that ends in `?`
+/// already has the right type without it, so it is returned as is instead of as `Ok(map?)`.
+pub fn wrap_ok(map: TokenStream) -> TokenStream {
+    let mut tokens = map.clone().into_iter().collect::<Vec<_>>();
+    match tokens.pop() {
+        Some(TokenTree::Punct(p)) if p.as_char() == '?' => tokens.into_iter().collect(),
+        _ => quote!(::core::result::Result::Ok(#map)),
+    }
+}
+
+/// Returns the mapping provided for the projection `path` of `param`. Projections qualified by a different trait than
+/// the provided one are different associated types, and are not mapped by it.
+fn find_projection<'a>(
+    attribute: &'a Attribute,
+    param: &Ident,
+    path: &TypePath,
+) -> Option<&'a Projection> {
+    let (_, assoc) = projection_of(path)?;
+    let trait_ = projection_trait(path).map(|path| path.to_token_stream().to_string());
+    attribute.projections.iter().find(|projection| {
+        &projection.param == param
+            && &projection.assoc == assoc
+            && match (&projection.trait_, &trait_) {
+                (Some(declared), Some(trait_)) => &declared.to_token_stream().to_string() == trait_,
+                _ => true,
+            }
+    })
+}
+
+/// Generates the mapping of `field` for `fmap_once`, whose mapping function can only be called once, together with the
+/// number of times it is called. Only bare parameters, tuples, markers and projections are mapped this way, as any other
+/// type may hold `param` more than once. If there is such a type, it is returned instead.
+pub fn generate_map_once<'a>(
+    typ: &'a Type,
+    param: &Ident,
+    field: &TokenStream,
+    attribute: &Attribute,
+) -> Result<(TokenStream, usize), &'a Type> {
+    if !type_contains_param(typ, param) {
+        return Ok((quote!(#field), 0));
+    }
+
+    match typ {
+        Type::Path(path) => {
+            if is_phantom_data(path) {
+                return Ok((
+                    generate_map_from_path(path, param, field, false, attribute)
+                        .unwrap()
+                        .0,
+                    0,
+                ));
+            }
+            if let Some((projected, _)) = projection_of(path) {
+                if projected == param {
+                    let via = &find_projection(attribute, param, path).ok_or(typ)?.via;
+                    return Ok((quote!(#via::<#param, __B>(#field)), 0));
+                }
+            }
+            match path.path.segments.iter().exactly_one() {
+                Ok(segment) if &segment.ident == param => Ok((quote!(__f(#field)), 1)),
+                _ => Err(typ),
+            }
+        }
+        Type::Tuple(tuple) => {
+            let mut count = 0;
+            let elems = tuple
+                .elems
+                .iter()
+                .enumerate()
+                .map(|(i, elem)| {
+                    let i = Index::from(i);
+                    let (map, n) = generate_map_once(elem, param, &quote!(#field.#i), attribute)?;
+                    count += n;
+                    Ok(quote!(#map,))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((quote!((#(#elems)*)), count))
+        }
+        Type::Paren(p) => generate_map_once(&p.elem, param, field, attribute),
+        Type::Group(g) => generate_map_once(&g.elem, param, field, attribute),
+        _ => Err(typ),
+    }
+}
+
+/// Generates a value of type `typ` for `from_fn`, which fills every occurrence of `param` by calling `__f` with its path
+/// from the root, of which `path` holds the steps so far. Other types are filled with their default, and are added to
+/// `defaults`. Only arrays, tuples and markers have a fixed number of occurrences, so any other type that holds `param`
+/// is returned instead.
+pub fn generate_fill<'a>(
+    typ: &'a Type,
+    param: &Ident,
+    path: &mut Vec<TokenStream>,
+    defaults: &mut Vec<&'a Type>,
+) -> Result<TokenStream, &'a Type> {
+    if !type_contains_param(typ, param) {
+        defaults.push(typ);
+        return Ok(quote!(<#param as ::functor_derive::DefaultField<#typ>>::default_field()));
+    }
+
+    match typ {
+        Type::Path(path_type) => {
+            if is_phantom_data(path_type) {
+                return Ok(quote!(::core::marker::PhantomData));
+            }
+            match path_type.path.segments.iter().exactly_one() {
+                Ok(segment) if &segment.ident == param => Ok(quote!(__f(&[#(#path),*]))),
+                _ => Err(typ),
+            }
+        }
+        Type::Tuple(tuple) => {
+            let elems = tuple
+                .elems
+                .iter()
+                .enumerate()
+                .map(|(i, elem)| {
+                    let name = i.to_string();
+                    path.push(quote!(::functor_derive::SlotStep::Field(#name)));
+                    let fill = generate_fill(elem, param, path, defaults);
+                    path.pop();
+                    let fill = fill?;
+                    Ok(quote!(#fill,))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(quote!((#(#elems)*)))
+        }
+        Type::Array(array) => {
+            let index = format_ident!("__i{}", path.len());
+            path.push(quote!(::functor_derive::SlotStep::Index(#index)));
+            let fill = generate_fill(&array.elem, param, path, defaults);
+            path.pop();
+            let fill = fill?;
+            Ok(quote!(::core::array::from_fn(|#index| #fill)))
+        }
+        Type::Paren(p) => generate_fill(&p.elem, param, path, defaults),
+        Type::Group(g) => generate_fill(&g.elem, param, path, defaults),
+        _ => Err(typ),
+    }
+}
+
+/// Aborts with a diagnostic if the parameter `param` occurs in the type `typ` in a position that cannot be mapped.
+pub fn check_mappable(typ: &Type, param: &Ident, attribute: &Attribute) {
+    if !type_contains_param(typ, param) {
+        return;
+    }
+
+    match typ {
+        Type::Path(path) if is_phantom_data(path) => {}
+        Type::Path(path) => {
+            if let Some((projected, assoc)) = projection_of(path) {
+                if projected == param {
+                    if find_projection(attribute, param, path).is_none() {
+                        let (source, target) = match projection_trait(path) {
+                            Some(trait_) => {
+                                let trait_ = trait_.to_token_stream().to_string().replace(' ', "");
+                                (format!("<{param} as {trait_}>"), format!("<B as {trait_}>"))
+                            }
+                            None => (param.to_string(), "B".to_string()),
+                        };
+                        abort!(
+                            path,
+                            "Cannot map the associated type projection `{}::{}`.", source, assoc;
+                            help = "Provide a mapping using `#[functor({}::{} => {}::{} via path::to::function)]`.", source, assoc, target, assoc
+                        )
+                    }
+                    return;
+                }
+            }
+
+            if let Some((_, boxed)) = boxed(path) {
+                if !attribute.lazy {
+                    let kind = match boxed {
+                        Boxed::Iterator(_) => "iterator",
+                        Boxed::FnOnce(_) => "closure",
+                    };
+                    abort!(
+                        path,
+                        "Cannot map `{}` inside a boxed {} without mapping it lazily.", param, kind;
+                        help = "Enable lazy mapping using `#[functor(lazy)]`, which requires the mapping function to be `Clone + 'static`."
+                    )
+                }
+                check_mappable(boxed.value(), param, attribute);
+                return;
+            }
+
+            if let Some((_, segment)) = boxed_trait(path) {
+                if ["Fn", "FnMut", "FnOnce"]
+                    .iter()
+                    .any(|name| segment.ident == name)
+                {
+                    abort!(
+                        segment,
+                        "Cannot map `{}` inside a boxed `{}` closure.", param, segment.ident;
+                        help = "Only `Box<dyn FnOnce() -> {}>` can be mapped, as `try_fmap` has to call the closure to report errors.", param
+                    )
+                }
+                if segment.ident == "Future" {
+                    abort!(
+                        segment,
+                        "Cannot map `{}` inside a boxed future.", param;
+                        help = "`try_fmap` would have to await the future to report errors."
+                    )
+                }
+            }
+
+            if let Some(qself) = &path.qself {
+                abort!(
+                    qself.ty,
+                    "Cannot map `{}` inside the self type of a qualified path.",
+                    param
+                )
+            }
+
+            if let Some(segment) = path.path.segments.last() {
+                if find_functor(attribute, &path.path) {
+                    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                        let count = args
+                            .args
+                            .iter()
+                            .filter(|arg| matches!(arg, GenericArgument::Type(typ) if type_contains_param(typ, param)))
+                            .count();
+                        if count > 1 {
+                            abort!(
+                                segment,
+                                "Cannot map `{}` inside more than one generic argument of `{}`, which is mapped through the `Functor` trait.", param, segment.ident;
+                                help = "The `Functor` trait maps a single parameter."
+                            )
+                        }
+                    }
+                }
+            }
+
+            for (idx, segment) in path.path.segments.iter().enumerate() {
+                let is_last = idx + 1 == path.path.segments.len();
+                match &segment.arguments {
+                    PathArguments::None => {}
+                    PathArguments::AngleBracketed(args) => {
+                        for (arg_idx, arg) in args.args.iter().enumerate() {
+                            match arg {
+                                GenericArgument::Type(typ)
+                                    if is_last
+                                        && is_hasher_position(&segment.ident, arg_idx)
+                                        && type_contains_param(typ, param) =>
+                                {
+                                    abort!(
+                                        typ,
+                                        "Cannot map `{}` inside the hasher of `{}`.", param, segment.ident;
+                                        help = "Entries would have to be rehashed with a hasher of a different type."
+                                    )
+                                }
+                                GenericArgument::Type(typ) if is_last => {
+                                    check_mappable(typ, param, attribute)
+                                }
+                                GenericArgument::Type(typ) if type_contains_param(typ, param) => {
+                                    abort!(
+                                        segment,
+                                        "Cannot map `{}` inside the generic arguments of `{}`, which is not the last segment of the path.", param, segment.ident
+                                    )
+                                }
+                                GenericArgument::AssocType(assoc)
+                                    if type_contains_param(&assoc.ty, param) =>
+                                {
+                                    abort!(
+                                        assoc,
+                                        "Cannot map `{}` inside the associated type binding `{}`.",
+                                        param,
+                                        assoc.ident
+                                    )
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    PathArguments::Parenthesized(_) => abort!(
+                        segment,
+                        "Cannot map `{}` inside the `Fn`-style arguments of `{}`.", param, segment.ident;
+                        help = "Closures cannot be mapped without calling them or capturing the mapping function."
+                    ),
+                }
+            }
+        }
+        Type::Array(array) => check_mappable(&array.elem, param, attribute),
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .for_each(|typ| check_mappable(typ, param, attribute)),
+        Type::Paren(paren) => check_mappable(&paren.elem, param, attribute),
+        Type::Group(group) => check_mappable(&group.elem, param, attribute),
+        Type::BareFn(_) => abort!(typ, "Cannot map `{}` inside a function pointer.", param),
+        Type::Reference(_) => abort!(typ, "Cannot map `{}` behind a reference.", param),
+        Type::Ptr(_) => abort!(typ, "Cannot map `{}` behind a raw pointer.", param),
+        Type::Slice(slice) => check_mappable(&slice.elem, param, attribute),
+        Type::TraitObject(obj) => {
+            for bound in &obj.bounds {
+                if let TypeParamBound::Trait(t) = bound {
+                    check_mappable(
+                        &Type::Path(TypePath {
+                            qself: None,
+                            path: t.path.clone(),
+                        }),
+                        param,
+                        attribute,
+                    );
+                }
+            }
+            abort!(typ, "Cannot map `{}` inside a trait object.", param)
+        }
+        _ => abort!(typ, "Cannot map `{}` inside this type.", param),
+    }
+}
+
+/// Returns whether the parameter `param` is absent from the generic arguments of all segments but the last.
+fn path_prefix_is_free_of_param(path: &Path, param: &Ident) -> bool {
+    let prefix_len = path.segments.len().saturating_sub(1);
+    !path
+        .segments
+        .iter()
+        .take(prefix_len)
+        .any(|segment| path_arguments_contain_param(&segment.arguments, param))
+}
+
+/// Returns whether the generic arguments `arguments` of a path segment contain the parameter `param`.
+fn path_arguments_contain_param(arguments: &PathArguments, param: &Ident) -> bool {
+    match arguments {
+        PathArguments::None => false,
+        PathArguments::AngleBracketed(args) => args.args.iter().any(|arg| match arg {
+            GenericArgument::Type(typ) => type_contains_param(typ, param),
+            GenericArgument::AssocType(assoc) => type_contains_param(&assoc.ty, param),
+            _ => false,
+        }),
+        PathArguments::Parenthesized(args) => {
+            args.inputs
+                .iter()
+                .any(|typ| type_contains_param(typ, param))
+                || matches!(&args.output, ReturnType::Type(_, typ) if type_contains_param(typ, param))
+        }
+    }
+}
+
+/// Returns the projected type and associated type if `path` is of the form `T::Assoc` or `<T as Trait>::Assoc`.
+pub fn projection_of(path: &TypePath) -> Option<(&Ident, &Ident)> {
+    let assoc = &path.path.segments.last()?.ident;
+    match &path.qself {
+        Some(qself) => match &*qself.ty {
+            Type::Path(TypePath { qself: None, path }) => Some((path.get_ident()?, assoc)),
+            _ => None,
+        },
+        None => match path.path.segments.iter().collect_tuple() {
+            Some((first, _)) if first.arguments.is_empty() && path.path.leading_colon.is_none() => {
+                Some((&first.ident, assoc))
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Returns the trait that qualifies the projection `path`, e.g. `Trait` if it is of the form `<T as Trait>::Assoc`.
+pub fn projection_trait(path: &TypePath) -> Option<Path> {
+    let qself = path.qself.as_ref()?;
+    Some(Path {
+        leading_colon: path.path.leading_colon,
+        segments: path
+            .path
+            .segments
+            .iter()
+            .take(qself.position)
+            .cloned()
+            .collect(),
+    })
 }
 
 /// Returns whether or not the given type `typ` contains the parameter `param`.
-fn type_contains_param(typ: &Type, param: &Ident) -> bool {
+pub fn type_contains_param(typ: &Type, param: &Ident) -> bool {
     match typ {
         Type::Path(path) => {
             // If the path consists of exactly one segment, then it must be the param.
@@ -142,18 +1068,19 @@ fn type_contains_param(typ: &Type, param: &Ident) -> bool {
                 _ => {}
             }
 
-            // If the path is empty, something weird happened.
-            let Some(last_segment) = path.path.segments.last() else {
-                return false;
-            };
+            // A projection such as `T::Assoc` changes together with `T`.
+            if matches!(projection_of(path), Some((projected, _)) if projected == param) {
+                return true;
+            }
 
-            let PathArguments::AngleBracketed(bracketed_params) = &last_segment.arguments else {
-                return false;
-            };
+            if matches!(&path.qself, Some(qself) if type_contains_param(&qself.ty, param)) {
+                return true;
+            }
 
-            bracketed_params.args.iter().any(|bracketed_param| {
-                matches!(bracketed_param, GenericArgument::Type(typ) if type_contains_param(typ, param))
-            })
+            path.path
+                .segments
+                .iter()
+                .any(|segment| path_arguments_contain_param(&segment.arguments, param))
         }
         Type::Array(array) => type_contains_param(&array.elem, param),
         Type::Tuple(tuple) => tuple.elems.iter().any(|t| type_contains_param(t, param)),
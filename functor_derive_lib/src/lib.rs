@@ -1,28 +1,38 @@
 #![doc = include_str!("../README.md")]
 
-use crate::generate_fmap_body::generate_fmap_body;
-use crate::map::{map_path, map_where};
-use crate::parse_attribute::parse_attribute;
-use proc_macro2::{Ident, TokenStream};
-use proc_macro_error::proc_macro_error;
+use crate::generate_fmap_body::{
+    generate_fmap_body, generate_fmap_once_body, generate_from_fn_body,
+};
+use crate::generate_map::{check_mappable, type_contains_param, wrap_ok};
+use crate::map::{map_path, map_type_param_bounds, map_where};
+use crate::parse_attribute::{parse_attribute, Attribute, Collisions, Once};
+use itertools::Itertools;
+use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro_error::{abort, abort_call_site, proc_macro_error};
 use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
 use syn::token::Colon;
 use syn::{
-    parse_macro_input, Data, DeriveInput, Expr, ExprPath, GenericArgument, GenericParam, Path,
-    PathSegment, PredicateType, TraitBound, TraitBoundModifier, Type, TypeParamBound, TypePath,
-    WhereClause, WherePredicate,
+    parse_macro_input, parse_quote, Data, DeriveInput, Expr, ExprPath, Field, GenericArgument,
+    GenericParam, Lifetime, Path, PathSegment, PredicateType, Token, TraitBound,
+    TraitBoundModifier, Type, TypeParamBound, TypePath, WhereClause, WherePredicate,
 };
 
+mod base_functor;
 mod generate_fmap_body;
 mod generate_map;
 mod map;
 mod parse_attribute;
+mod stack_safe;
 
 #[proc_macro_derive(Functor, attributes(functor))]
 #[proc_macro_error]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    derive_functor(input).into()
+}
 
+fn derive_functor(input: DeriveInput) -> TokenStream {
     // Name of the Struct or Enum we are implementing the `Functor` trait for.
     let def_name = input.ident.clone();
 
@@ -82,44 +92,275 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         #[automatically_derived]
     };
 
+    // Only mapped parameters can get `fmap_once`.
+    if let Once::Params(params) = &attribute.once {
+        for param in params {
+            if attribute.default.as_ref() != Some(param)
+                && attribute.name_map.iter().all(|(named, _)| named != param)
+            {
+                abort!(
+                    param,
+                    "`fmap_once` was enabled for `{}`, which is not mapped.",
+                    param
+                )
+            }
+        }
+    }
+
+    // Report parameters that are requested but occur in positions that cannot be mapped.
+    for param in attribute
+        .default
+        .iter()
+        .chain(attribute.name_map.iter().map(|(param, _)| param))
+    {
+        for field in fields(&input.data) {
+            check_mappable(&field.ty, param, &attribute);
+        }
+    }
+
+    // A mirror of a foreign type only maps values of the foreign type.
+    if let Some(remote) = &attribute.remote {
+        return generate_remote_impl(
+            &input.data,
+            &def_name,
+            remote,
+            &source_params,
+            &source_args,
+            &input.generics.where_clause,
+            &attribute,
+            &lints,
+        );
+    }
+
     let mut tokens = TokenStream::new();
 
+    // Stack-safe types map their recursive fields through a layer, in which these are replaced by a parameter.
+    let layer = (attribute.stack_safe && stack_safe::is_recursive(&input, &source_args))
+        .then(|| format_ident!("__{def_name}Layer"));
+    if let Some(layer) = &layer {
+        tokens.extend(stack_safe::generate_layer(&input, layer));
+    }
+
     // Include default Functor implementation.
-    if let Some(default) = attribute.default {
+    if let Some(default) = &attribute.default {
+        let bounds = param_bounds(&input.data, default, &attribute);
         tokens.extend(generate_default_impl(
-            &default,
+            default,
+            &def_name,
+            &source_params,
+            &source_args,
+            &input.generics.where_clause,
+            &bounds,
+            &lints,
+        ));
+        tokens.extend(generate_family_impl(
+            default,
             &def_name,
             &source_params,
             &source_args,
             &input.generics.where_clause,
+            &bounds,
             &lints,
         ));
+        if attribute.once.includes(default) {
+            tokens.extend(generate_once_impl(
+                default,
+                &format_ident!("fmap_once"),
+                &input.data,
+                &def_name,
+                &source_params,
+                &source_args,
+                &input.generics.where_clause,
+                &attribute,
+                &lints,
+            ));
+        }
+        if attribute.choices {
+            tokens.extend(generate_choices_impl(
+                default,
+                &format_ident!("fmap_all_choices"),
+                &format_ident!("fmap_all_choices_iter"),
+                &format_ident!("fmap"),
+                &def_name,
+                &source_params,
+                &source_args,
+                &input.generics.where_clause,
+                &bounds,
+                &lints,
+            ));
+        }
+        if attribute.transpose {
+            tokens.extend(generate_transpose_impl(
+                default,
+                &format_ident!("transpose"),
+                &format_ident!("try_fmap"),
+                &def_name,
+                &source_params,
+                &source_args,
+                &input.generics.where_clause,
+                &bounds,
+                &lints,
+            ));
+        }
+        if attribute.from_fn {
+            tokens.extend(generate_from_fn_impl(
+                default,
+                &format_ident!("from_fn"),
+                &format_ident!("replicate"),
+                &input.data,
+                &def_name,
+                &source_params,
+                &source_args,
+                &input.generics.where_clause,
+                &lints,
+            ));
+        }
     }
 
     // Include all named implementations.
-    for (param, name) in attribute.name_map {
+    for (param, name) in &attribute.name_map {
+        let bounds = param_bounds(&input.data, param, &attribute);
         tokens.extend(generate_named_impl(
-            &param,
-            &name,
+            param,
+            name,
             &def_name,
             &source_params,
             &source_args,
             &input.generics.where_clause,
+            &bounds,
             &lints,
         ));
+        if attribute.once.includes(param) {
+            tokens.extend(generate_once_impl(
+                param,
+                &format_ident!("fmap_once_{name}"),
+                &input.data,
+                &def_name,
+                &source_params,
+                &source_args,
+                &input.generics.where_clause,
+                &attribute,
+                &lints,
+            ));
+        }
+        if attribute.choices {
+            tokens.extend(generate_choices_impl(
+                param,
+                &format_ident!("fmap_all_choices_{name}"),
+                &format_ident!("fmap_all_choices_iter_{name}"),
+                &format_ident!("fmap_{name}"),
+                &def_name,
+                &source_params,
+                &source_args,
+                &input.generics.where_clause,
+                &bounds,
+                &lints,
+            ));
+        }
+        if attribute.transpose {
+            tokens.extend(generate_transpose_impl(
+                param,
+                &format_ident!("transpose_{name}"),
+                &format_ident!("try_fmap_{name}"),
+                &def_name,
+                &source_params,
+                &source_args,
+                &input.generics.where_clause,
+                &bounds,
+                &lints,
+            ));
+        }
+        if attribute.from_fn {
+            tokens.extend(generate_from_fn_impl(
+                param,
+                &format_ident!("from_fn_{name}"),
+                &format_ident!("replicate_{name}"),
+                &input.data,
+                &def_name,
+                &source_params,
+                &source_args,
+                &input.generics.where_clause,
+                &lints,
+            ));
+        }
     }
 
     // Include internal implementations.
     tokens.extend(generate_refs_impl(
-        &input.data,
+        &input,
+        layer.as_ref(),
         &def_name,
         &source_params,
         &source_args,
         &input.generics.where_clause,
+        &attribute,
         &lints,
     ));
 
-    tokens.into()
+    tokens
+}
+
+/// Derives the base functor `{Name}F<R>` of a recursive type, see the recursion schemes section of the README.
+#[proc_macro_derive(BaseFunctor)]
+#[proc_macro_error]
+pub fn derive_base_functor(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    base_functor::derive_base_functor(input).into()
+}
+
+fn fields(data: &Data) -> Vec<&Field> {
+    match data {
+        Data::Struct(strct) => strct.fields.iter().collect(),
+        Data::Enum(enm) => enm.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(_) => vec![],
+    }
+}
+
+/// Bounds that mapping a parameter needs beyond the ones written on the type.
+struct ParamBounds {
+    /// The bounds that the mapping function and `__B` need to map lazily, if enabled.
+    lazy: Option<Punctuated<TypeParamBound, Token![+]>>,
+    /// The predicates on key and set element types, like `__B: Hash + Eq` for a `HashSet<T>` field.
+    keys: Vec<WherePredicate>,
+    /// The mapped key types whose collisions `try_fmap` reports under the strict policy, like `__B` for a `HashSet<T>`.
+    collisions: Vec<Type>,
+}
+
+fn param_bounds(data: &Data, param: &Ident, attribute: &Attribute) -> ParamBounds {
+    let mut keys = Vec::new();
+    let mut collisions = Vec::new();
+    for field in fields(data) {
+        generate_map::key_bounds(&field.ty, param, &mut keys);
+        if matches!(attribute.collisions, Collisions::Strict) {
+            generate_map::collision_keys(&field.ty, param, &mut collisions);
+        }
+    }
+
+    ParamBounds {
+        lazy: lazy_bounds(data, param, attribute),
+        keys,
+        collisions,
+    }
+}
+
+/// Returns the bounds that the mapping function and `__B` need to map `param` lazily, if enabled.
+fn lazy_bounds(
+    data: &Data,
+    param: &Ident,
+    attribute: &Attribute,
+) -> Option<Punctuated<TypeParamBound, Token![+]>> {
+    if !attribute.lazy {
+        return None;
+    }
+
+    let mut bounds = vec![TypeParamBound::Lifetime(Lifetime::new(
+        "'static",
+        Span::call_site(),
+    ))];
+    for field in fields(data) {
+        generate_map::lazy_bounds(&field.ty, param, &mut bounds);
+    }
+    Some(bounds.into_iter().collect())
 }
 
 fn find_index(source_params: &[GenericParam], ident: &Ident) -> usize {
@@ -132,14 +373,18 @@ fn find_index(source_params: &[GenericParam], ident: &Ident) -> usize {
     unreachable!()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_refs_impl(
-    data: &Data,
+    input: &DeriveInput,
+    layer: Option<&Ident>,
     def_name: &Ident,
     source_params: &Vec<GenericParam>,
     source_args: &Vec<GenericArgument>,
     where_clause: &Option<WhereClause>,
+    attribute: &Attribute,
     lints: &TokenStream,
 ) -> TokenStream {
+    let data = &input.data;
     let mut tokens = TokenStream::new();
     for param in source_params {
         if let GenericParam::Type(t) = param {
@@ -150,36 +395,62 @@ fn generate_refs_impl(
             let fmap_ident = format_ident!("__fmap_{param_idx}_ref");
             let try_fmap_ident = format_ident!("__try_fmap_{param_idx}_ref");
 
-            // Generate body of the `fmap` implementation.
-            let Some(fmap_ref_body) = generate_fmap_body(data, def_name, &param_ident, false)
-            else {
-                continue;
-            };
-            let Some(try_fmap_ref_body) = generate_fmap_body(data, def_name, &param_ident, true)
-            else {
-                continue;
-            };
-
             let mut target_args = source_args.clone();
             target_args[param_idx] = GenericArgument::Type(Type::Path(TypePath {
                 qself: None,
                 path: Path::from(PathSegment::from(format_ident!("__B"))),
             }));
 
+            // Generate the traversal, which is shared by `fmap` unless it maps lazily. Parameters that cannot be mapped
+            // are skipped, also when mapping through the layer of a stack-safe type.
+            let generate_body = |is_try| {
+                let body = generate_fmap_body(
+                    data,
+                    &quote!(#def_name),
+                    None,
+                    &param_ident,
+                    is_try,
+                    attribute,
+                )?;
+                Some(match layer {
+                    Some(layer) => stack_safe::generate_stack_safe_body(
+                        input,
+                        layer,
+                        param_idx,
+                        source_args,
+                        &target_args,
+                        is_try,
+                    ),
+                    None => body,
+                })
+            };
+            let Some(try_fmap_ref_body) = generate_body(true).map(wrap_ok) else {
+                continue;
+            };
+            let fmap_ref_body = if maps_separately(attribute) {
+                generate_body(false).unwrap()
+            } else {
+                map_infallibly(|mapper| quote!(self.#try_fmap_ident(#mapper)))
+            };
+
+            let bounds = param_bounds(data, &param_ident, attribute);
+            let mapper_bounds = mapper_bounds(&bounds);
+
             if let Some(fn_where_clause) =
-                create_fn_where_clause(where_clause, source_params, &param_ident)
+                create_fn_where_clause(where_clause, source_params, &param_ident, &bounds)
             {
+                let try_where_clause = try_where_clause(&fn_where_clause, &quote!(__E), &bounds);
                 tokens.extend(quote!(
                     #lints
                     impl<#(#source_params),*> #def_name<#(#source_args),*> #where_clause {
-                        pub fn #fmap_ident<__B>(self, __f: &impl Fn(#param_ident) -> __B) -> #def_name<#(#target_args),*> #fn_where_clause {
+                        pub fn #fmap_ident<__B>(self, __f: &(impl Fn(#param_ident) -> __B #mapper_bounds)) -> #def_name<#(#target_args),*> #fn_where_clause {
                             use ::functor_derive::*;
                             #fmap_ref_body
                         }
 
-                        pub fn #try_fmap_ident<__B, __E>(self, __f: &impl Fn(#param_ident) -> Result<__B, __E>) -> Result<#def_name<#(#target_args),*>, __E> #fn_where_clause {
+                        pub fn #try_fmap_ident<__B, __E>(self, __f: &(impl Fn(#param_ident) -> ::core::result::Result<__B, __E> #mapper_bounds)) -> ::core::result::Result<#def_name<#(#target_args),*>, __E> #try_where_clause {
                             use ::functor_derive::*;
-                            Ok(#try_fmap_ref_body)
+                            #try_fmap_ref_body
                         }
                     }
                 ))
@@ -194,9 +465,9 @@ fn generate_refs_impl(
                             #fmap_ref_body
                         }
 
-                        fn #try_fmap_ident<__B, __E>(self, __f: &impl Fn(#param_ident) -> Result<__B, __E>) -> Result<#def_name<#(#target_args),*>, __E> {
+                        fn #try_fmap_ident<__B, __E>(self, __f: &impl Fn(#param_ident) -> ::core::result::Result<__B, __E>) -> ::core::result::Result<#def_name<#(#target_args),*>, __E> {
                             use ::functor_derive::*;
-                            Ok(#try_fmap_ref_body)
+                            #try_fmap_ref_body
                         }
                     }
                 ))
@@ -206,15 +477,146 @@ fn generate_refs_impl(
     tokens
 }
 
+/// Returns whether `fmap` needs a traversal of its own, instead of mapping through `try_fmap`. Lazy mappings capture
+/// the mapping function, and the strict collision policy panics where `try_fmap` fails.
+fn maps_separately(attribute: &Attribute) -> bool {
+    attribute.lazy || matches!(attribute.collisions, Collisions::Strict)
+}
+
+/// Maps through the fallible traversal built by `try_fmap` from a mapper, which cannot fail as its errors are `Infallible`.
+fn map_infallibly(try_fmap: impl FnOnce(TokenStream) -> TokenStream) -> TokenStream {
+    let try_fmap = try_fmap(quote!(&|__v| ::core::result::Result::Ok::<
+        _,
+        ::core::convert::Infallible,
+    >(__f(__v))));
+    quote!(match #try_fmap {
+        ::core::result::Result::Ok(__target) => __target,
+        ::core::result::Result::Err(__never) => match __never {},
+    })
+}
+
+/// Generates associated functions on the mirror `def_name` that map values of the foreign type `remote`, which has the
+/// same fields. The foreign type cannot implement the positional traits here, so fields of it are mapped through these.
+#[allow(clippy::too_many_arguments)]
+fn generate_remote_impl(
+    data: &Data,
+    def_name: &Ident,
+    remote: &Path,
+    source_params: &Vec<GenericParam>,
+    source_args: &Vec<GenericArgument>,
+    where_clause: &Option<WhereClause>,
+    attribute: &Attribute,
+    lints: &TokenStream,
+) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    for param in source_params {
+        let GenericParam::Type(t) = param else {
+            continue;
+        };
+        let param_ident = &t.ident;
+        let param_idx = find_index(source_params, param_ident);
+
+        let fmap_ident = format_ident!("__fmap_{param_idx}_ref");
+        let try_fmap_ident = format_ident!("__try_fmap_{param_idx}_ref");
+
+        let remote_path = quote!(#remote);
+        let generate_body = |is_try| {
+            generate_fmap_body(
+                data,
+                &remote_path,
+                Some(&quote!(__this)),
+                param_ident,
+                is_try,
+                attribute,
+            )
+        };
+        let Some(try_fmap_ref_body) = generate_body(true).map(wrap_ok) else {
+            continue;
+        };
+        let fmap_ref_body = if maps_separately(attribute) {
+            generate_body(false).unwrap()
+        } else {
+            map_infallibly(|mapper| quote!(Self::#try_fmap_ident(__this, #mapper)))
+        };
+
+        let mut target_args = source_args.clone();
+        target_args[param_idx] = GenericArgument::Type(Type::Path(TypePath {
+            qself: None,
+            path: Path::from(PathSegment::from(format_ident!("__B"))),
+        }));
+
+        let bounds = param_bounds(data, param_ident, attribute);
+        let mapper_bounds = mapper_bounds(&bounds);
+        let fn_where_clause =
+            create_fn_where_clause(where_clause, source_params, param_ident, &bounds);
+        let try_where_clause = fn_where_clause
+            .as_ref()
+            .map(|fn_where_clause| try_where_clause(fn_where_clause, &quote!(__E), &bounds));
+
+        let source = quote!(#remote<#(#source_args),*>);
+        let target = quote!(#remote<#(#target_args),*>);
+
+        // The default and named mappings of the mirror map values of the foreign type as well.
+        let names = attribute
+            .default
+            .iter()
+            .filter(|default| *default == param_ident)
+            .map(|_| (format_ident!("fmap"), format_ident!("try_fmap")))
+            .chain(
+                attribute
+                    .name_map
+                    .iter()
+                    .filter(|(param, _)| param == param_ident)
+                    .map(|(_, name)| {
+                        (
+                            format_ident!("fmap_{name}"),
+                            format_ident!("try_fmap_{name}"),
+                        )
+                    }),
+            )
+            .collect::<Vec<_>>();
+        let fmap_names = names.iter().map(|(fmap, _)| fmap);
+        let try_fmap_names = names.iter().map(|(_, try_fmap)| try_fmap);
+
+        tokens.extend(quote!(
+            #lints
+            impl<#(#source_params),*> #def_name<#(#source_args),*> #where_clause {
+                pub fn #fmap_ident<__B>(__this: #source, __f: &(impl Fn(#param_ident) -> __B #mapper_bounds)) -> #target #fn_where_clause {
+                    use ::functor_derive::*;
+                    #fmap_ref_body
+                }
+
+                pub fn #try_fmap_ident<__B, __E>(__this: #source, __f: &(impl Fn(#param_ident) -> ::core::result::Result<__B, __E> #mapper_bounds)) -> ::core::result::Result<#target, __E> #try_where_clause {
+                    use ::functor_derive::*;
+                    #try_fmap_ref_body
+                }
+
+                #(
+                    pub fn #fmap_names<__B>(__this: #source, __f: impl Fn(#param_ident) -> __B #mapper_bounds) -> #target #fn_where_clause {
+                        Self::#fmap_ident(__this, &__f)
+                    }
+
+                    pub fn #try_fmap_names<__B, __E>(__this: #source, __f: impl Fn(#param_ident) -> ::core::result::Result<__B, __E> #mapper_bounds) -> ::core::result::Result<#target, __E> #try_where_clause {
+                        Self::#try_fmap_ident(__this, &__f)
+                    }
+                )*
+            }
+        ));
+    }
+    tokens
+}
+
 fn generate_default_impl(
     param: &Ident,
     def_name: &Ident,
     source_params: &Vec<GenericParam>,
     source_args: &Vec<GenericArgument>,
     where_clause: &Option<WhereClause>,
+    bounds: &ParamBounds,
     lints: &TokenStream,
 ) -> TokenStream {
     let default_idx = find_index(source_params, param);
+    let mapper_bounds = mapper_bounds(bounds);
 
     // Create generic arguments for the target. We use `__B` for the mapped generic.
     let mut target_args = source_args.clone();
@@ -226,16 +628,19 @@ fn generate_default_impl(
     let default_map = format_ident!("__fmap_{default_idx}_ref");
     let default_try_map = format_ident!("__try_fmap_{default_idx}_ref");
 
-    if let Some(fn_where_clause) = create_fn_where_clause(where_clause, source_params, param) {
+    if let Some(fn_where_clause) =
+        create_fn_where_clause(where_clause, source_params, param, bounds)
+    {
+        let try_where_clause = try_where_clause(&fn_where_clause, &quote!(__E), bounds);
         quote!(
             #lints
             impl<#(#source_params),*> #def_name<#(#source_args),*> #where_clause {
-                pub fn fmap<__B>(self, __f: impl Fn(#param) -> __B) -> #def_name<#(#target_args),*> #fn_where_clause {
+                pub fn fmap<__B>(self, __f: impl Fn(#param) -> __B #mapper_bounds) -> #def_name<#(#target_args),*> #fn_where_clause {
                     use ::functor_derive::*;
                     self.#default_map(&__f)
                 }
 
-                pub fn try_fmap<__B, __E>(self, __f: impl Fn(#param) -> Result<__B, __E>) -> Result<#def_name<#(#target_args),*>, __E> #fn_where_clause {
+                pub fn try_fmap<__B, __E>(self, __f: impl Fn(#param) -> ::core::result::Result<__B, __E> #mapper_bounds) -> ::core::result::Result<#def_name<#(#target_args),*>, __E> #try_where_clause {
                     use ::functor_derive::*;
                     self.#default_try_map(&__f)
                 }
@@ -252,7 +657,7 @@ fn generate_default_impl(
                     self.#default_map(&__f)
                 }
 
-                fn try_fmap<__B, __E>(self, __f: impl Fn(#param) -> Result<__B, __E>) -> Result<#def_name<#(#target_args),*>, __E> {
+                fn try_fmap<__B, __E>(self, __f: impl Fn(#param) -> ::core::result::Result<__B, __E>) -> ::core::result::Result<#def_name<#(#target_args),*>, __E> {
                     use ::functor_derive::*;
                     self.#default_try_map(&__f)
                 }
@@ -261,6 +666,50 @@ fn generate_default_impl(
     }
 }
 
+/// Generates the `FunctorFamily` implementation for the default parameter `param`, if its members need no bounds.
+fn generate_family_impl(
+    param: &Ident,
+    def_name: &Ident,
+    source_params: &Vec<GenericParam>,
+    source_args: &Vec<GenericArgument>,
+    where_clause: &Option<WhereClause>,
+    bounds: &ParamBounds,
+    lints: &TokenStream,
+) -> TokenStream {
+    // Every `Member<__A>` must be a valid type, so neither `param` nor the mapping may be bounded.
+    // Lifetime parameters may require `__A` to outlive them, which cannot be stated either.
+    if is_bounded(param, source_params, where_clause, bounds)
+        || source_params
+            .iter()
+            .any(|param| matches!(param, GenericParam::Lifetime(_)))
+    {
+        return TokenStream::new();
+    }
+
+    let default_idx = find_index(source_params, param);
+    let mut member_args = source_args.clone();
+    member_args[default_idx] = GenericArgument::Type(Type::Path(TypePath {
+        qself: None,
+        path: Path::from(PathSegment::from(format_ident!("__A"))),
+    }));
+
+    quote!(
+        #lints
+        impl<#(#source_params),*> ::functor_derive::FunctorFamily for #def_name<#(#source_args),*> #where_clause {
+            type Member<__A> = #def_name<#(#member_args),*>;
+
+            fn fmap_member<__A, __B>(__x: Self::Member<__A>, __f: impl Fn(__A) -> __B) -> Self::Member<__B> {
+                __x.fmap(__f)
+            }
+
+            fn try_fmap_member<__A, __B, __E>(__x: Self::Member<__A>, __f: impl Fn(__A) -> ::core::result::Result<__B, __E>) -> ::core::result::Result<Self::Member<__B>, __E> {
+                __x.try_fmap(__f)
+            }
+        }
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
 fn generate_named_impl(
     param: &Ident,
     name: &Ident,
@@ -268,6 +717,7 @@ fn generate_named_impl(
     source_params: &Vec<GenericParam>,
     source_args: &Vec<GenericArgument>,
     where_clause: &Option<WhereClause>,
+    bounds: &ParamBounds,
     lints: &TokenStream,
 ) -> TokenStream {
     let default_idx = find_index(source_params, param);
@@ -279,23 +729,28 @@ fn generate_named_impl(
         path: Path::from(PathSegment::from(format_ident!("__B"))),
     }));
 
+    let mapper_bounds = mapper_bounds(bounds);
+
     let fmap_name = format_ident!("fmap_{name}");
     let try_fmap_name = format_ident!("try_fmap_{name}");
 
     let fmap = format_ident!("__fmap_{default_idx}_ref");
     let fmap_try = format_ident!("__try_fmap_{default_idx}_ref");
 
-    let fn_where_clause = create_fn_where_clause(where_clause, source_params, param);
+    let fn_where_clause = create_fn_where_clause(where_clause, source_params, param, bounds);
+    let try_where_clause = fn_where_clause
+        .as_ref()
+        .map(|fn_where_clause| try_where_clause(fn_where_clause, &quote!(__E), bounds));
 
     quote!(
         #lints
         impl<#(#source_params),*> #def_name<#(#source_args),*> #where_clause {
-            pub fn #fmap_name<__B>(self, __f: impl Fn(#param) -> __B) -> #def_name<#(#target_args),*> #fn_where_clause {
+            pub fn #fmap_name<__B>(self, __f: impl Fn(#param) -> __B #mapper_bounds) -> #def_name<#(#target_args),*> #fn_where_clause {
                 use ::functor_derive::*;
                 self.#fmap(&__f)
             }
 
-            pub fn #try_fmap_name<__B, __E>(self, __f: impl Fn(#param) -> Result<__B, __E>) -> Result<#def_name<#(#target_args),*>, __E> #fn_where_clause {
+            pub fn #try_fmap_name<__B, __E>(self, __f: impl Fn(#param) -> ::core::result::Result<__B, __E> #mapper_bounds) -> ::core::result::Result<#def_name<#(#target_args),*>, __E> #try_where_clause {
                 use ::functor_derive::*;
                 self.#fmap_try(&__f)
             }
@@ -303,10 +758,256 @@ fn generate_named_impl(
     )
 }
 
+/// Generates `fmap_once`, which maps the single occurrence of `param` with a function that can only be called once.
+#[allow(clippy::too_many_arguments)]
+fn generate_once_impl(
+    param: &Ident,
+    fmap_once_name: &Ident,
+    data: &Data,
+    def_name: &Ident,
+    source_params: &Vec<GenericParam>,
+    source_args: &Vec<GenericArgument>,
+    where_clause: &Option<WhereClause>,
+    attribute: &Attribute,
+    lints: &TokenStream,
+) -> TokenStream {
+    let idx = find_index(source_params, param);
+    let mut target_args = source_args.clone();
+    target_args[idx] = GenericArgument::Type(Type::Path(TypePath {
+        qself: None,
+        path: Path::from(PathSegment::from(format_ident!("__B"))),
+    }));
+
+    let body = generate_fmap_once_body(data, def_name, param, attribute);
+    let fn_where_clause = create_fn_where_clause(
+        where_clause,
+        source_params,
+        param,
+        &param_bounds(data, param, attribute),
+    );
+
+    quote!(
+        #lints
+        impl<#(#source_params),*> #def_name<#(#source_args),*> #where_clause {
+            pub fn #fmap_once_name<__B>(self, __f: impl FnOnce(#param) -> __B) -> #def_name<#(#target_args),*> #fn_where_clause {
+                #body
+            }
+        }
+    )
+}
+
+/// Generates `from_fn`, which constructs a struct by filling every occurrence of `param` from its path, and `replicate`,
+/// which fills them with clones of one value.
+#[allow(clippy::too_many_arguments)]
+fn generate_from_fn_impl(
+    param: &Ident,
+    from_fn_name: &Ident,
+    replicate_name: &Ident,
+    data: &Data,
+    def_name: &Ident,
+    source_params: &Vec<GenericParam>,
+    source_args: &Vec<GenericArgument>,
+    where_clause: &Option<WhereClause>,
+    lints: &TokenStream,
+) -> TokenStream {
+    let (body, defaults) = generate_from_fn_body(data, param);
+    // Field types with generics are bound to be `Default`. Such a bound on a field type without generics would be
+    // checked right away, so these are bound through the parameter instead, which defers them to where `from_fn` is
+    // called.
+    let defaults = defaults
+        .into_iter()
+        .unique_by(|typ| quote!(#typ).to_string())
+        .map(|typ| {
+            let is_generic = source_params.iter().any(|generic| {
+                matches!(generic, GenericParam::Type(generic) if type_contains_param(typ, &generic.ident))
+            });
+            if is_generic {
+                quote!(#typ: ::core::default::Default)
+            } else {
+                quote!(#param: ::functor_derive::DefaultField<#typ>)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    quote!(
+        #lints
+        impl<#(#source_params),*> #def_name<#(#source_args),*> #where_clause {
+            /// Constructs a value by calling `__f` with the path of every occurrence of the parameter, in the order of
+            /// the fields. Fields that do not hold the parameter are filled with their default.
+            pub fn #from_fn_name(mut __f: impl FnMut(&[::functor_derive::SlotStep]) -> #param) -> Self
+            where
+                #(#defaults,)*
+            {
+                #body
+            }
+
+            /// Constructs a value with a clone of `value` at every occurrence of the parameter.
+            pub fn #replicate_name(value: #param) -> Self
+            where
+                #param: ::core::clone::Clone,
+                #(#defaults,)*
+            {
+                Self::#from_fn_name(|_| ::core::clone::Clone::clone(&value))
+            }
+        }
+    )
+}
+
+/// Returns the bounds of the function that maps `param` lazily, which adapters keep a copy of.
+fn mapper_bounds(bounds: &ParamBounds) -> Option<TokenStream> {
+    bounds
+        .lazy
+        .as_ref()
+        .map(|bounds| quote!(+ ::functor_derive::LazyMapper + #bounds))
+}
+
+/// Returns whether `param`, or the function that maps it, is bounded, such that not every type can be mapped to.
+fn is_bounded(
+    param: &Ident,
+    source_params: &Vec<GenericParam>,
+    where_clause: &Option<WhereClause>,
+    bounds: &ParamBounds,
+) -> bool {
+    let mut is_bounded =
+        bounds.lazy.is_some() || !bounds.keys.is_empty() || !bounds.collisions.is_empty();
+    if let Some(where_clause) = where_clause {
+        is_bounded |= map_where(where_clause, param).is_some();
+    }
+    for source_param in source_params {
+        if let GenericParam::Type(typ) = source_param {
+            is_bounded |= &typ.ident == param && !typ.bounds.is_empty();
+            map_type_param_bounds(&mut typ.bounds.clone(), param, &mut is_bounded);
+        }
+    }
+    is_bounded
+}
+
+/// Generates `fmap_all_choices` and `fmap_all_choices_iter`, which rebuild the structure for every combination of
+/// alternatives of `param` through `fmap`. Unlike `FunctorChoices`, this does not require a `FunctorFamily`
+/// implementation, so it is also available for types with lifetime parameters.
+#[allow(clippy::too_many_arguments)]
+fn generate_choices_impl(
+    param: &Ident,
+    choices_name: &Ident,
+    choices_iter_name: &Ident,
+    fmap_name: &Ident,
+    def_name: &Ident,
+    source_params: &Vec<GenericParam>,
+    source_args: &Vec<GenericArgument>,
+    where_clause: &Option<WhereClause>,
+    bounds: &ParamBounds,
+    lints: &TokenStream,
+) -> TokenStream {
+    // The structure is rebuilt from a shape holding indices, so it must be possible to map `param` to any type.
+    if is_bounded(param, source_params, where_clause, bounds) {
+        abort_call_site!(
+            "Cannot generate `{}` for `{}`, because it is bounded or mapped lazily.",
+            choices_name,
+            param;
+            help = "Only parameters without bounds, whose mapping needs no bounds either, can be mapped to every combination of choices."
+        )
+    }
+
+    let idx = find_index(source_params, param);
+    let mut target_args = source_args.clone();
+    target_args[idx] = GenericArgument::Type(parse_quote!(__B));
+    let mut shape_args = source_args.clone();
+    shape_args[idx] = GenericArgument::Type(parse_quote!(usize));
+
+    let mut choices_where_clause =
+        create_fn_where_clause(where_clause, source_params, param, bounds)
+            .expect("a where clause is always created");
+    choices_where_clause
+        .predicates
+        .push(parse_quote!(#def_name<#(#shape_args),*>: ::core::clone::Clone));
+
+    quote!(
+        #lints
+        impl<#(#source_params),*> #def_name<#(#source_args),*> #where_clause {
+            pub fn #choices_name<__B: ::core::clone::Clone>(self, __f: impl Fn(#param) -> ::functor_derive::__Vec<__B>) -> ::functor_derive::__Vec<#def_name<#(#target_args),*>> #choices_where_clause {
+                ::core::iter::Iterator::collect(self.#choices_iter_name(__f))
+            }
+
+            pub fn #choices_iter_name<__B: ::core::clone::Clone>(self, __f: impl Fn(#param) -> ::functor_derive::__Vec<__B>) -> impl ::core::iter::Iterator<Item = #def_name<#(#target_args),*>> #choices_where_clause {
+                ::functor_derive::__all_choices(
+                    |__index| self.#fmap_name(__index),
+                    __f,
+                    |__shape: #def_name<#(#shape_args),*>, __pick| __shape.#fmap_name(__pick),
+                )
+            }
+        }
+    )
+}
+
+/// Generates `transpose`, which turns a structure of `Option`s or `Result`s in `param` inside out through `try_fmap`.
+#[allow(clippy::too_many_arguments)]
+fn generate_transpose_impl(
+    param: &Ident,
+    transpose_name: &Ident,
+    try_fmap_name: &Ident,
+    def_name: &Ident,
+    source_params: &Vec<GenericParam>,
+    source_args: &Vec<GenericArgument>,
+    where_clause: &Option<WhereClause>,
+    bounds: &ParamBounds,
+    lints: &TokenStream,
+) -> TokenStream {
+    let idx = find_index(source_params, param);
+    let mut target_args = source_args.clone();
+    target_args[idx] = GenericArgument::Type(Type::Path(TypePath {
+        qself: None,
+        path: Path::from(PathSegment::from(format_ident!("__B"))),
+    }));
+
+    let transpose_where_clause = create_fn_where_clause(where_clause, source_params, param, bounds)
+        .map(|fn_where_clause| {
+            let residual = quote!(<#param as ::functor_derive::Fallible>::Residual);
+            transpose_where_clause(
+                &try_where_clause(&fn_where_clause, &residual, bounds),
+                param,
+            )
+        });
+
+    quote!(
+        #lints
+        impl<#(#source_params),*> #def_name<#(#source_args),*> #where_clause {
+            pub fn #transpose_name<__B>(self) -> <#param as ::functor_derive::Fallible>::Wrapped<#def_name<#(#target_args),*>> #transpose_where_clause {
+                <#param as ::functor_derive::Fallible>::from_result(self.#try_fmap_name(::functor_derive::Fallible::into_result))
+            }
+        }
+    )
+}
+
+/// Extends the where clause of `fmap` with the bounds that the `error` type of `try_fmap` needs, which must be
+/// convertible from the collisions that the strict policy reports.
+fn try_where_clause(
+    fn_where_clause: &WhereClause,
+    error: &TokenStream,
+    bounds: &ParamBounds,
+) -> WhereClause {
+    let mut where_clause = fn_where_clause.clone();
+    for key in &bounds.collisions {
+        where_clause.predicates.push(parse_quote!(
+            #error: ::core::convert::From<::functor_derive::KeyCollision<#key>>
+        ));
+    }
+    where_clause
+}
+
+/// Extends the where clause of `try_fmap` with `param: Fallible<Ok = __B>`, which `transpose` maps out of.
+fn transpose_where_clause(fn_where_clause: &WhereClause, param: &Ident) -> WhereClause {
+    let mut where_clause = fn_where_clause.clone();
+    where_clause
+        .predicates
+        .push(parse_quote!(#param: ::functor_derive::Fallible<Ok = __B>));
+    where_clause
+}
+
 fn create_fn_where_clause(
     where_clause: &Option<WhereClause>,
     source_params: &Vec<GenericParam>,
     param: &Ident,
+    bounds: &ParamBounds,
 ) -> Option<WhereClause> {
     let mut predicates = where_clause
         .iter()
@@ -397,6 +1098,36 @@ fn create_fn_where_clause(
         .collect(),
     }));
 
+    // Add param: 'static and __B: 'static when mapping lazily, as the adapters capture both.
+    if let Some(lazy_bounds) = &bounds.lazy {
+        predicates.push(WherePredicate::Type(PredicateType {
+            lifetimes: None,
+            bounded_ty: Type::Path(TypePath {
+                qself: None,
+                path: Path::from(PathSegment::from(param.clone())),
+            }),
+            colon_token: Colon::default(),
+            bounds: [TypeParamBound::Lifetime(Lifetime::new(
+                "'static",
+                Span::call_site(),
+            ))]
+            .into_iter()
+            .collect(),
+        }));
+        predicates.push(WherePredicate::Type(PredicateType {
+            lifetimes: None,
+            bounded_ty: Type::Path(TypePath {
+                qself: None,
+                path: Path::from(PathSegment::from(format_ident!("__B"))),
+            }),
+            colon_token: Colon::default(),
+            bounds: lazy_bounds.clone(),
+        }));
+    }
+
+    // Add the bounds that keys and set elements need, before and after mapping.
+    predicates.extend(bounds.keys.iter().cloned());
+
     if predicates.is_empty() {
         None
     } else {
@@ -406,3 +1137,57 @@ fn create_fn_where_clause(
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::TokenTree;
+
+    /// Returns how many of the tokens in `tokens`, including those inside groups, satisfy `predicate`.
+    fn count_tokens(tokens: TokenStream, predicate: &impl Fn(&TokenTree) -> bool) -> usize {
+        tokens
+            .into_iter()
+            .map(|token| match token {
+                TokenTree::Group(group) => count_tokens(group.stream(), predicate),
+                token => predicate(&token) as usize,
+            })
+            .sum()
+    }
+
+    /// Derives `Functor` on an enum with `variants` variants, with the given attribute.
+    fn derive_enum(variants: usize, attribute: TokenStream) -> TokenStream {
+        let variants = (0..variants).map(|i| {
+            let variant = format_ident!("Op{i}");
+            quote!(#variant { args: Vec<T>, span: S })
+        });
+        derive_functor(parse_quote!(
+            #attribute
+            enum Instr<T, S> {
+                #(#variants),*
+            }
+        ))
+    }
+
+    #[test]
+    fn variants_are_traversed_once_per_parameter() {
+        // Each parameter has one traversal, shared by `fmap`, `try_fmap` and the aliases, which names every variant
+        // once in its pattern and once in the rebuilt value.
+        let tokens = derive_enum(3, quote!(#[functor(T as values, S as spans)]));
+        let matches = count_tokens(
+            tokens,
+            &|token| matches!(token, TokenTree::Ident(ident) if ident == "Op1"),
+        );
+        assert_eq!(matches, 2 * 2);
+    }
+
+    #[test]
+    fn aliases_do_not_grow_with_the_type() {
+        // The named `fmap_{name}` and `try_fmap_{name}` only forward to the traversal.
+        let size = |variants, attribute| count_tokens(derive_enum(variants, attribute), &|_| true);
+        let alias = |variants| {
+            size(variants, quote!(#[functor(T, S as spans)]))
+                - size(variants, quote!(#[functor(T)]))
+        };
+        assert_eq!(alias(1), alias(30));
+    }
+}
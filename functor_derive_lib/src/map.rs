@@ -89,7 +89,7 @@ pub fn map_path(path: &mut Path, param: &Ident, contains_param: &mut bool) {
 }
 
 /// Maps the given parameter `param` in the type `typ` to `__B`.
-fn map_type(typ: &mut Type, param: &Ident, contains_param: &mut bool) {
+pub fn map_type(typ: &mut Type, param: &Ident, contains_param: &mut bool) {
     match typ {
         Type::Array(array) => {
             map_type(&mut array.elem, param, contains_param);
@@ -1,14 +1,23 @@
+use crate::generate_map::{projection_of, projection_trait};
 use proc_macro2::{Ident, Span};
 use proc_macro_error::{abort, abort_call_site};
-use quote::format_ident;
+use quote::{format_ident, ToTokens};
 use std::collections::HashSet;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{parse, DeriveInput, GenericParam, Meta, Token};
+use syn::{parse2, DeriveInput, GenericParam, Meta, Path, Token, TypePath};
 
 pub fn parse_attribute(input: &DeriveInput) -> Attribute {
-    functor_param_from_attrs(input).unwrap_or_else(|| functor_param_first(input))
+    match functor_param_from_attrs(input) {
+        // An attribute that only configures projections still maps the first generic by default.
+        Some(mut attribute) if attribute.default.is_none() && attribute.name_map.is_empty() => {
+            attribute.default = functor_param_first(input).default;
+            attribute
+        }
+        Some(attribute) => attribute,
+        None => functor_param_first(input),
+    }
 }
 
 fn functor_param_first(input: &DeriveInput) -> Attribute {
@@ -20,7 +29,7 @@ fn functor_param_first(input: &DeriveInput) -> Attribute {
             if let GenericParam::Type(typ) = param {
                 Some(Attribute {
                     default: Some(typ.ident.clone()),
-                    name_map: vec![],
+                    ..Attribute::default()
                 })
             } else {
                 None
@@ -50,7 +59,8 @@ fn functor_param_from_attrs(input: &DeriveInput) -> Option<Attribute> {
                 abort!(span, "Found two functor attributes",)
             }
             let span = list.tokens.span();
-            let param = parse(list.tokens.clone().into()).unwrap();
+            let param =
+                parse2(list.tokens.clone()).unwrap_or_else(|err| abort!(err.span(), "{}", err));
             functor_attribute = Some((param, span));
         }
     }
@@ -58,16 +68,104 @@ fn functor_param_from_attrs(input: &DeriveInput) -> Option<Attribute> {
     functor_attribute.map(|(param, _)| param)
 }
 
-#[derive(Debug)]
+#[derive(Default)]
 pub struct Attribute {
     pub default: Option<Ident>,
     pub name_map: Vec<(Ident, Ident)>,
+    pub projections: Vec<Projection>,
+    /// Whether boxed iterator trait objects are mapped lazily, requiring a `Clone + 'static` mapping function.
+    pub lazy: bool,
+    /// Which parameters get `fmap_once`, which requires them to occur exactly once in every variant.
+    pub once: Once,
+    /// Whether `from_fn` and `replicate` are generated, which require a struct with a fixed number of parameters.
+    pub from_fn: bool,
+    /// Whether `fmap_all_choices` is generated, which requires the mapped parameters to be unbounded.
+    pub choices: bool,
+    /// Whether `transpose` is generated, which would otherwise clash with a method of the same name on the type.
+    pub transpose: bool,
+    /// Whether recursive fields are mapped with an explicit work stack instead of by recursion.
+    pub stack_safe: bool,
+    /// What happens when keys of a map or elements of a set are mapped to the same key.
+    pub collisions: Collisions,
+    /// The foreign type that this type mirrors, whose values are mapped instead of the values of this type.
+    pub remote: Option<Path>,
+    /// The foreign types that are mapped through a mirror, e.g. `Spanned via SpannedDef`.
+    pub remotes: Vec<Remote>,
+    /// The types that are mapped through the public `Functor` trait, e.g. `Wrapper: Functor`.
+    pub functors: Vec<Path>,
+}
+
+#[derive(Default)]
+pub enum Once {
+    /// No parameter gets `fmap_once`.
+    #[default]
+    Disabled,
+    /// Every mapped parameter gets `fmap_once`, enabled by `once`.
+    All,
+    /// Only the given parameters get `fmap_once`, enabled by `once = T` for each of them.
+    Params(Vec<Ident>),
+}
+
+impl Once {
+    pub fn includes(&self, param: &Ident) -> bool {
+        match self {
+            Once::Disabled => false,
+            Once::All => true,
+            Once::Params(params) => params.contains(param),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub enum Collisions {
+    /// The entry that comes last is kept, which is the behaviour of the collections themselves.
+    #[default]
+    KeepLast,
+    /// Mapping panics, detected by comparing the length of the collection before and after. `try_fmap` panics too, as
+    /// the error type belongs to the mapping function and need not be able to describe a collision.
+    Panic,
+    /// `try_fmap` fails with the colliding key, which the error type must be convertible from, and `fmap` panics.
+    Strict,
+    /// The values of colliding keys are combined with the given function, e.g. `merge via combine`, which is given the
+    /// value that comes first as its first argument. Sets keep the element that comes first.
+    Merge(Path),
+}
+
+/// Describes how an associated type projection of a parameter is mapped, e.g. `T::Id => B::Id via map_id`.
+pub struct Projection {
+    /// The parameter that is projected, e.g. `T`.
+    pub param: Ident,
+    /// The associated type that is projected, e.g. `Id`.
+    pub assoc: Ident,
+    /// The trait that qualifies the projection, e.g. `Lang` in `<T as Lang>::Id`.
+    pub trait_: Option<Path>,
+    /// The function that maps the projection, called as `map_id::<T, B>(value)`.
+    pub via: Path,
+}
+
+/// Describes a foreign type that is mapped through a mirror, e.g. `other::Spanned via SpannedDef`.
+pub struct Remote {
+    /// The foreign type, which is recognised by the path it is written with.
+    pub ty: Path,
+    /// The mirror, which was derived with `#[functor(remote = other::Spanned)]`.
+    pub via: Path,
 }
 
 impl Parse for Attribute {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut default = None;
         let mut name_map = Vec::new();
+        let mut projections = Vec::<Projection>::new();
+        let mut lazy = false;
+        let mut once = Once::Disabled;
+        let mut from_fn = false;
+        let mut choices = false;
+        let mut transpose = false;
+        let mut stack_safe = false;
+        let mut collisions = None;
+        let mut remote = None;
+        let mut remotes = Vec::<Remote>::new();
+        let mut functors = Vec::<Path>::new();
         let mut seen_names = HashSet::new();
 
         for sub_attr in Punctuated::<SubAttribute, Token![,]>::parse_separated_nonempty(input)? {
@@ -83,26 +181,220 @@ impl Parse for Attribute {
                     }
                     name_map.push((param, name));
                 }
+                SubAttribute::Projection(projection) => {
+                    if projections.iter().any(|p| {
+                        p.param == projection.param
+                            && p.assoc == projection.assoc
+                            && p.trait_.as_ref().map(path_string)
+                                == projection.trait_.as_ref().map(path_string)
+                    }) {
+                        abort!(
+                            projection.assoc,
+                            "Two mappings for the same projection were provided."
+                        )
+                    }
+                    projections.push(projection);
+                }
+                SubAttribute::Lazy => {
+                    if std::mem::replace(&mut lazy, true) {
+                        abort_call_site!("Lazy mapping was enabled twice.")
+                    }
+                }
+                SubAttribute::Once(param) => {
+                    once = match (once, param) {
+                        (Once::Disabled, None) => Once::All,
+                        (Once::Disabled, Some(param)) => Once::Params(vec![param]),
+                        (Once::Params(mut params), Some(param)) => {
+                            if params.contains(&param) {
+                                abort!(param, "`fmap_once` was enabled twice for `{}`.", param)
+                            }
+                            params.push(param);
+                            Once::Params(params)
+                        }
+                        _ => abort_call_site!("`fmap_once` was enabled twice."),
+                    }
+                }
+                SubAttribute::FromFn => {
+                    if std::mem::replace(&mut from_fn, true) {
+                        abort_call_site!("`from_fn` was enabled twice.")
+                    }
+                }
+                SubAttribute::Choices => {
+                    if std::mem::replace(&mut choices, true) {
+                        abort_call_site!("`fmap_all_choices` was enabled twice.")
+                    }
+                }
+                SubAttribute::Transpose => {
+                    if std::mem::replace(&mut transpose, true) {
+                        abort_call_site!("`transpose` was enabled twice.")
+                    }
+                }
+                SubAttribute::StackSafe => {
+                    if std::mem::replace(&mut stack_safe, true) {
+                        abort_call_site!("Stack-safe mapping was enabled twice.")
+                    }
+                }
+                SubAttribute::Collisions(policy) => {
+                    if collisions.replace(policy).is_some() {
+                        abort_call_site!("Two collision policies were provided.")
+                    }
+                }
+                SubAttribute::Mirror(path) => {
+                    if remote.replace(path).is_some() {
+                        abort_call_site!("Two remote types were provided.")
+                    }
+                }
+                SubAttribute::Remote(mirror) => {
+                    if remotes.iter().any(|other| same_path(&other.ty, &mirror.ty)) {
+                        abort!(
+                            mirror.ty,
+                            "Two mirrors for the same remote type were provided."
+                        )
+                    }
+                    remotes.push(mirror);
+                }
+                SubAttribute::Functor(path) => {
+                    if functors.iter().any(|other| same_path(other, &path)) {
+                        abort!(
+                            path,
+                            "The same type was declared to implement `Functor` twice."
+                        )
+                    }
+                    functors.push(path);
+                }
             }
         }
 
-        Ok(Attribute { default, name_map })
+        Ok(Attribute {
+            default,
+            name_map,
+            projections,
+            lazy,
+            once,
+            from_fn,
+            choices,
+            transpose,
+            stack_safe,
+            collisions: collisions.unwrap_or(Collisions::KeepLast),
+            remote,
+            remotes,
+            functors,
+        })
     }
 }
 
 enum SubAttribute {
     Default(Ident),
     NameMap(Ident, Ident),
+    Projection(Projection),
+    Lazy,
+    Once(Option<Ident>),
+    FromFn,
+    Choices,
+    Transpose,
+    StackSafe,
+    Collisions(Collisions),
+    Mirror(Path),
+    Remote(Remote),
+    Functor(Path),
+}
+
+/// Returns whether the type of a field, written as `path`, is the type `declared` in the attribute. Types are recognised
+/// by the path they are written with, so `a::Wrapper` and `b::Wrapper` are different types. Generic arguments only have
+/// to match where they are declared, e.g. `Wrapper<u8>: Functor` does not apply to `Wrapper<T>`.
+pub fn is_declared(declared: &Path, path: &Path) -> bool {
+    declared.leading_colon.is_some() == path.leading_colon.is_some()
+        && declared.segments.len() == path.segments.len()
+        && declared
+            .segments
+            .iter()
+            .zip(&path.segments)
+            .all(|(declared, segment)| {
+                declared.ident == segment.ident
+                    && (declared.arguments.is_none()
+                        || declared.arguments.to_token_stream().to_string()
+                            == segment.arguments.to_token_stream().to_string())
+            })
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    path_string(a) == path_string(b)
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_token_stream().to_string()
 }
 
 impl Parse for SubAttribute {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![<]) || input.peek2(Token![::]) || input.peek2(Token![<]) {
+            let source = input.parse::<TypePath>()?;
+            if input.peek(via) && source.qself.is_none() {
+                input.parse::<via>()?;
+                let via = input.parse::<Path>()?;
+                return Ok(SubAttribute::Remote(Remote {
+                    ty: source.path,
+                    via,
+                }));
+            }
+            if input.peek(Token![:]) && source.qself.is_none() {
+                return parse_functor_bound(input).map(|_| SubAttribute::Functor(source.path));
+            }
+            return parse_projection(source, input).map(SubAttribute::Projection);
+        }
+
         let param = input.parse::<Ident>()?;
 
         let sub_attr = if input.peek(Token![as]) {
             input.parse::<Token![as]>()?;
             let name = input.parse::<Ident>()?;
             SubAttribute::NameMap(param, name)
+        } else if param == "collisions" && input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            let policy = input.parse::<Ident>()?;
+            if policy == "keep_last" {
+                SubAttribute::Collisions(Collisions::KeepLast)
+            } else if policy == "panic" {
+                SubAttribute::Collisions(Collisions::Panic)
+            } else if policy == "strict" {
+                SubAttribute::Collisions(Collisions::Strict)
+            } else if policy == "merge" {
+                input.parse::<via>()?;
+                SubAttribute::Collisions(Collisions::Merge(input.parse::<Path>()?))
+            } else {
+                return Err(syn::Error::new(
+                    policy.span(),
+                    "Expected a collision policy: `keep_last`, `panic`, `strict` or `merge via path::to::function`.",
+                ));
+            }
+        } else if param == "remote" && input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            SubAttribute::Mirror(input.parse::<Path>()?)
+        } else if input.peek(via) {
+            input.parse::<via>()?;
+            let via = input.parse::<Path>()?;
+            SubAttribute::Remote(Remote {
+                ty: Path::from(param),
+                via,
+            })
+        } else if input.peek(Token![:]) {
+            parse_functor_bound(input)?;
+            SubAttribute::Functor(Path::from(param))
+        } else if param == "lazy" {
+            SubAttribute::Lazy
+        } else if param == "once" && input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            SubAttribute::Once(Some(input.parse::<Ident>()?))
+        } else if param == "once" {
+            SubAttribute::Once(None)
+        } else if param == "from_fn" {
+            SubAttribute::FromFn
+        } else if param == "choices" {
+            SubAttribute::Choices
+        } else if param == "transpose" {
+            SubAttribute::Transpose
+        } else if param == "stack_safe" {
+            SubAttribute::StackSafe
         } else {
             SubAttribute::Default(param)
         };
@@ -110,3 +402,68 @@ impl Parse for SubAttribute {
         Ok(sub_attr)
     }
 }
+
+syn::custom_keyword!(via);
+
+/// Parses the `: Functor` that declares a type to implement the public `Functor` trait.
+fn parse_functor_bound(input: ParseStream) -> syn::Result<()> {
+    input.parse::<Token![:]>()?;
+    let bound = input.parse::<Path>()?;
+    match bound.segments.last() {
+        Some(segment) if segment.ident == "Functor" && segment.arguments.is_empty() => Ok(()),
+        _ => Err(syn::Error::new(
+            bound.span(),
+            "Expected the `Functor` trait, as in `Wrapper: Functor`.",
+        )),
+    }
+}
+
+fn parse_projection(source: TypePath, input: ParseStream) -> syn::Result<Projection> {
+    let Some((param, assoc)) = projection_of(&source) else {
+        return Err(syn::Error::new(
+            source.span(),
+            "Expected a projection of the form `T::Assoc` or `<T as Trait>::Assoc`.",
+        ));
+    };
+
+    input.parse::<Token![=>]>()?;
+
+    let target = input.parse::<TypePath>()?;
+    match projection_of(&target) {
+        Some((target_param, target_assoc)) if target_assoc == assoc => {
+            // The target projects the type that the parameter is mapped to, which is called `B` like in `fmap`.
+            if target_param != "B" {
+                return Err(syn::Error::new(
+                    target_param.span(),
+                    format!("Expected the target to project `B`, the type that `{param}` is mapped to, as in `B::{assoc}`."),
+                ));
+            }
+        }
+        _ => {
+            return Err(syn::Error::new(
+                target.span(),
+                format!("Expected the target to project the same associated type `{assoc}`."),
+            ))
+        }
+    }
+
+    let trait_ = projection_trait(&source);
+    if let Some(target_trait) = projection_trait(&target) {
+        if trait_.as_ref().map(path_string) != Some(path_string(&target_trait)) {
+            return Err(syn::Error::new(
+                target_trait.span(),
+                "Expected the target to be qualified by the same trait as the projection.",
+            ));
+        }
+    }
+
+    input.parse::<via>()?;
+    let via = input.parse::<Path>()?;
+
+    Ok(Projection {
+        param: param.clone(),
+        assoc: assoc.clone(),
+        trait_,
+        via,
+    })
+}
@@ -0,0 +1,188 @@
+use crate::base_functor::{fields_mut, generate_conversion, generic_args, replace_recursion};
+use crate::fields;
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::abort;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, Data, DeriveInput, GenericArgument, GenericParam, PathArguments, ReturnType, Type,
+    Visibility,
+};
+
+/// Returns whether a field of `input` refers to `input` itself, in which case mapping it recurses.
+/// Every such field must have the generic arguments `args`, so that all nodes fit in the same layer.
+pub fn is_recursive(input: &DeriveInput, args: &[GenericArgument]) -> bool {
+    let mut is_recursive = false;
+    for field in fields(&input.data) {
+        find_recursion(&field.ty, &input.ident, args, &mut is_recursive);
+    }
+    is_recursive
+}
+
+fn find_recursion(typ: &Type, name: &Ident, args: &[GenericArgument], found: &mut bool) {
+    let mut find = |typ: &Type| find_recursion(typ, name, args, found);
+    match typ {
+        Type::Array(array) => find(&array.elem),
+        Type::Group(group) => find(&group.elem),
+        Type::Paren(paren) => find(&paren.elem),
+        Type::Slice(slice) => find(&slice.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().for_each(find),
+        Type::Path(path) => {
+            if path.qself.is_none() && path.path.segments.len() == 1 {
+                let segment = &path.path.segments[0];
+                if segment.ident == "Self" {
+                    *found = true;
+                    return;
+                }
+                if segment.ident == *name {
+                    let is_same = match &segment.arguments {
+                        PathArguments::AngleBracketed(arguments) => arguments
+                            .args
+                            .iter()
+                            .map(|arg| arg.to_token_stream().to_string())
+                            .eq(args.iter().map(|arg| arg.to_token_stream().to_string())),
+                        PathArguments::None => args.is_empty(),
+                        PathArguments::Parenthesized(_) => false,
+                    };
+                    if !is_same {
+                        abort!(
+                            path,
+                            "Stack-safe mapping requires recursive fields to have the same generic arguments as `{}`.",
+                            name
+                        )
+                    }
+                    *found = true;
+                    return;
+                }
+            }
+            for segment in &path.path.segments {
+                match &segment.arguments {
+                    PathArguments::AngleBracketed(arguments) => {
+                        for arg in &arguments.args {
+                            if let GenericArgument::Type(typ) = arg {
+                                find(typ);
+                            }
+                        }
+                    }
+                    PathArguments::Parenthesized(arguments) => {
+                        arguments.inputs.iter().for_each(&mut find);
+                        if let ReturnType::Type(_, typ) = &arguments.output {
+                            find(typ);
+                        }
+                    }
+                    PathArguments::None => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Generates the layer `layer_name<.., __R>` of `input`: the same type with every recursive position replaced by `__R`.
+/// It is derived with the same `functor` attribute, so that its values are mapped exactly like those of `input`.
+pub fn generate_layer(input: &DeriveInput, layer_name: &Ident) -> TokenStream {
+    let mut layer = input.clone();
+    layer.ident = layer_name.clone();
+    layer.vis = Visibility::Inherited;
+    layer.attrs.retain(|attr| attr.path().is_ident("functor"));
+    for param in &mut layer.generics.params {
+        match param {
+            GenericParam::Type(param) => param.default = None,
+            GenericParam::Const(param) => param.default = None,
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+    layer.generics.params.push(parse_quote!(__R));
+
+    let rec_type: Type = parse_quote!(__R);
+    let args = generic_args(input);
+    for fields in fields_mut(&mut layer.data) {
+        for field in fields.iter_mut() {
+            field.attrs.clear();
+            replace_recursion(&mut field.ty, &input.ident, &args, &rec_type, false);
+        }
+    }
+    if let Data::Enum(enm) = &mut layer.data {
+        for variant in &mut enm.variants {
+            variant.attrs.clear();
+        }
+    }
+
+    quote!(
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #[allow(non_camel_case_types)]
+        #[derive(::functor_derive::Functor)]
+        #layer
+    )
+}
+
+/// Generates the body of `__fmap_{param_idx}_ref` for a stack-safe type. Instead of recursing into its children, each
+/// node is projected to its layer, whose values are mapped directly and whose children are left to the work queue.
+pub fn generate_stack_safe_body(
+    input: &DeriveInput,
+    layer_name: &Ident,
+    param_idx: usize,
+    source_args: &[GenericArgument],
+    target_args: &[GenericArgument],
+    is_try: bool,
+) -> TokenStream {
+    let name = &input.ident;
+    let source = quote!(#name<#(#source_args),*>);
+    let target = quote!(#name<#(#target_args),*>);
+
+    // The recursive positions are the last parameter of the layer.
+    let rec_idx = input.generics.params.len();
+    let fmap_children = format_ident!("__fmap_{rec_idx}_ref");
+
+    let project = generate_conversion(
+        input,
+        &quote!(__node),
+        &quote!(#name),
+        &quote!(#layer_name),
+        &source,
+        true,
+    );
+    let embed = generate_conversion(
+        input,
+        &quote!(__layer),
+        &quote!(#layer_name),
+        &quote!(#name),
+        &target,
+        false,
+    );
+
+    let (map_values, layer, map_stack_safe) = if is_try {
+        let try_fmap = format_ident!("__try_fmap_{param_idx}_ref");
+        (
+            quote!(__layer.#try_fmap(__f)?),
+            quote!(::core::result::Result::Ok::<_, __E>(__layer)),
+            quote!(::functor_derive::__try_map_stack_safe),
+        )
+    } else {
+        let fmap = format_ident!("__fmap_{param_idx}_ref");
+        (
+            quote!(__layer.#fmap(__f)),
+            quote!(__layer),
+            quote!(::functor_derive::__map_stack_safe),
+        )
+    };
+    let mapped = quote!(#map_stack_safe(
+        self,
+        |__node: #source, __push: &dyn Fn(#source) -> usize| {
+            let __layer = #project;
+            let __layer = #map_values;
+            let __layer = __layer.#fmap_children(&|__child| __push(__child));
+            #layer
+        },
+        |__layer: #layer_name<#(#target_args,)* usize>, __take: &dyn Fn(usize) -> #target| {
+            let __layer = __layer.#fmap_children(&|__index| __take(__index));
+            #embed
+        },
+    ));
+
+    if is_try {
+        quote!(#mapped?)
+    } else {
+        mapped
+    }
+}
@@ -0,0 +1,38 @@
+//! Derives `Functor` in a `no_std` crate that only has `alloc`, so the generated code must not refer to `std`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ops::Range;
+use functor_derive::Functor;
+
+#[derive(Functor)]
+pub struct Registers<T> {
+    pub current: Cell<T>,
+    pub previous: Option<(T, u8)>,
+    pub banks: [T; 2],
+    pub window: Range<T>,
+    pub marker: PhantomData<T>,
+}
+
+#[derive(Functor)]
+#[functor(collisions = panic)]
+pub struct Buffers<T: Clone> {
+    pub samples: Vec<T>,
+    pub frames: alloc::boxed::Box<[T]>,
+    pub shared: Rc<T>,
+    pub by_channel: BTreeMap<u8, T>,
+    pub active: BTreeSet<T>,
+}
+
+#[derive(Functor)]
+#[functor(lazy)]
+pub struct Pending<T> {
+    pub items: alloc::boxed::Box<dyn Iterator<Item = T>>,
+}
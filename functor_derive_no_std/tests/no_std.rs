@@ -0,0 +1,64 @@
+use core::cell::Cell;
+use core::marker::PhantomData;
+use functor_derive_no_std::{Buffers, Pending, Registers};
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+#[test]
+fn core_types() {
+    let x = Registers {
+        current: Cell::new(1usize),
+        previous: Some((2usize, 3)),
+        banks: [4usize, 5],
+        window: 6usize..7,
+        marker: PhantomData,
+    };
+
+    let y = x.fmap(|x| x as u64);
+    assert_eq!(y.current.get(), 1u64);
+    assert_eq!(y.previous, Some((2u64, 3)));
+    assert_eq!(y.banks, [4u64, 5]);
+    assert_eq!(y.window, 6u64..7);
+}
+
+#[test]
+fn alloc_types() {
+    let x = Buffers {
+        samples: vec![1usize],
+        frames: Box::new([2usize]),
+        shared: Rc::new(3usize),
+        by_channel: BTreeMap::from([(0, 4usize)]),
+        active: BTreeSet::from([5usize]),
+    };
+
+    let y = x.try_fmap(|x| Ok::<_, ()>(x as u64)).unwrap();
+    assert_eq!(y.samples, vec![1u64]);
+    assert_eq!(*y.frames, [2u64]);
+    assert_eq!(*y.shared, 3u64);
+    assert_eq!(y.by_channel, BTreeMap::from([(0, 4u64)]));
+    assert_eq!(y.active, BTreeSet::from([5u64]));
+}
+
+#[test]
+fn lazy() {
+    let x = Pending {
+        items: Box::new(vec![1usize, 2].into_iter()),
+    };
+
+    assert_eq!(
+        x.fmap(|x| x as u64).items.collect::<Vec<_>>(),
+        vec![1u64, 2]
+    );
+
+    let x = Pending {
+        items: Box::new(vec![1usize, 2].into_iter()),
+    };
+
+    assert_eq!(
+        x.try_fmap(|x| Ok::<_, ()>(x as u64))
+            .unwrap()
+            .items
+            .collect::<Vec<_>>(),
+        vec![1u64, 2]
+    );
+}